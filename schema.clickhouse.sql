@@ -0,0 +1,48 @@
+-- Table of the `db_out_clickhouse` module for substreams-sink-sql (ClickHouse).
+-- Rows are only inserted, one per reward of every farm event (or one without reward columns for an
+-- event without rewards). Rows written again when a range is re-processed share their id and are
+-- collapsed by the ReplacingMergeTree.
+-- Amounts are raw token units, empty strings and zeros stand for the fields an event kind doesn't have.
+
+CREATE TABLE IF NOT EXISTS farm_events_flat
+(
+    -- {event_id}/{reward_position}
+    id                      String,
+    -- {signature}:{instruction_index}[:{inner_instruction_index}]
+    event_id                String,
+    reward_position         UInt32,
+    -- initialize, restart, extend, restart_or_add (previous schedule unknown), new_reward, deposit,
+    -- withdraw, harvest, creator_withdraw_reward
+    kind                    LowCardinality(String),
+    signature               String,
+    slot                    UInt64,
+    -- unix timestamp, in seconds
+    block_time              Int64,
+    transaction_index       UInt32,
+    instruction_index       UInt32,
+    -- -1 for top-level invocations
+    inner_instruction_index Int64,
+    ordinal                 UInt32,
+    -- success or failed
+    status                  LowCardinality(String),
+    error                   String,
+    farm_id                 String,
+    user_address            String,
+    fee_payer               String,
+    lp_mint                 String,
+    lp_amount               UInt64,
+    start_time              UInt32,
+    end_time                UInt32,
+    reward_vault            String,
+    reward_mint             String,
+    reward_decimals         UInt32,
+    -- paid out by deposit, withdraw, harvest and creator_withdraw_reward
+    reward_amount           UInt64,
+    -- set by initialize, restart, extend and new_reward
+    reward_per_second       UInt64,
+    reward_start_time       UInt32,
+    reward_end_time         UInt32
+)
+ENGINE = ReplacingMergeTree()
+PRIMARY KEY (id)
+ORDER BY (id);
@@ -0,0 +1,92 @@
+-- Tables of the `db_out` module for substreams-sink-sql (Postgres).
+-- Amounts are raw token units.
+
+create table if not exists farms
+(
+    -- farm id
+    id                 text not null constraint farms_pk primary key,
+    lp_mint            text,
+    creator            text,
+    creation_slot      bigint,
+    creation_signature text,
+    updated_slot       bigint
+);
+
+create table if not exists reward_schedules
+(
+    -- farm:{farm_id}:reward:{reward_vault}
+    id                text not null constraint reward_schedules_pk primary key,
+    farm_id           text not null,
+    reward_vault      text not null,
+    reward_mint       text,
+    reward_decimals   integer,
+    reward_per_second numeric,
+    start_time        bigint,
+    end_time          bigint,
+    updated_slot      bigint
+);
+
+create index if not exists reward_schedules_farm_id_idx on reward_schedules (farm_id);
+
+create table if not exists farm_events
+(
+    -- {signature}:{instruction_index}[:{inner_instruction_index}]
+    id                      text not null constraint farm_events_pk primary key,
+    -- initialize, restart, extend, restart_or_add (previous schedule unknown), new_reward, deposit,
+    -- withdraw, harvest, creator_withdraw_reward
+    kind                    text not null,
+    signature               text not null,
+    farm_id                 text not null,
+    user_address            text,
+    fee_payer               text,
+    lp_mint                 text,
+    lp_amount               numeric,
+    start_time              bigint,
+    end_time                bigint,
+    slot                    bigint not null,
+    block_time              bigint not null,
+    transaction_index       integer not null,
+    instruction_index       integer not null,
+    inner_instruction_index integer,
+    ordinal                 integer not null,
+    -- success or failed
+    status                  text not null,
+    error                   text
+);
+
+create index if not exists farm_events_farm_id_idx on farm_events (farm_id);
+create index if not exists farm_events_user_address_idx on farm_events (user_address);
+create index if not exists farm_events_slot_idx on farm_events (slot);
+
+create table if not exists farm_event_rewards
+(
+    -- {event_id}/{reward_position}
+    id                text not null constraint farm_event_rewards_pk primary key,
+    event_id          text not null,
+    reward_position   integer not null,
+    reward_vault      text,
+    reward_mint       text,
+    reward_decimals   integer,
+    -- paid out by deposit, withdraw, harvest and creator_withdraw_reward
+    reward_amount     numeric,
+    -- set by initialize, restart, extend and new_reward
+    reward_per_second numeric,
+    start_time        bigint,
+    end_time          bigint
+);
+
+create index if not exists farm_event_rewards_event_id_idx on farm_event_rewards (event_id);
+
+create table if not exists positions
+(
+    -- {farm_id}:{user}
+    id                 text not null constraint positions_pk primary key,
+    farm_id            text not null,
+    user_address       text not null,
+    staked             numeric,
+    first_deposit_slot bigint,
+    last_activity_slot bigint
+);
+
+create index if not exists positions_farm_id_idx on positions (farm_id);
+create index if not exists positions_user_address_idx on positions (user_address);
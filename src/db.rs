@@ -0,0 +1,346 @@
+// Rows of the SQL sinks: `db_out` (Postgres, schema.sql) creates a farm, reward schedule or position
+// row on its first store delta and updates it afterwards, events and their rewards are immutable
+// and only created. substreams-sink-sql undoes the rows of a forked block itself.
+// `db_out_clickhouse` (schema.clickhouse.sql) only appends to a single wide event table.
+
+use substreams::pb::substreams::store_delta::Operation;
+use substreams::store::{DeltaProto, Deltas};
+use substreams_database_change::tables::{Row, Tables};
+
+use crate::keys::{self, FarmKey};
+use crate::pb::raydium_eco_farms::{
+    raydium_farm_transaction::Event, Farm, PositionChanges, RaydiumEcoFarmTransactions,
+    RaydiumFarmTransaction, RewardPayout, RewardSchedule, RewardUpdateKind, TransactionStatus,
+};
+
+// The fields shared by the events, flattened out of the `Event` variants
+#[derive(Debug, Default)]
+pub struct EventFields {
+    pub kind: &'static str,
+    pub signature: String,
+    pub farm_id: String,
+    pub user: String,
+    pub lp_mint: String,
+    pub lp_amount: u64,
+    pub start_time: u32,
+    pub end_time: u32,
+}
+
+pub fn event_fields(event: &Event) -> EventFields {
+    match event {
+        Event::Initialize(initialize_txn) => EventFields {
+            kind: "initialize",
+            signature: initialize_txn.signature.clone(),
+            farm_id: initialize_txn.farm_id.clone(),
+            user: initialize_txn.user.clone(),
+            lp_mint: initialize_txn.lp_mint.clone(),
+            start_time: initialize_txn.start_time,
+            end_time: initialize_txn.end_time,
+            ..Default::default()
+        },
+        Event::RestartOrAdd(restart_or_add_txn) => EventFields {
+            kind: restart_or_add_kind(restart_or_add_txn.update_kind()),
+            signature: restart_or_add_txn.signature.clone(),
+            farm_id: restart_or_add_txn.farm_id.clone(),
+            user: restart_or_add_txn.user.clone(),
+            lp_mint: restart_or_add_txn.lp_mint.clone(),
+            start_time: restart_or_add_txn.start_time,
+            end_time: restart_or_add_txn.end_time,
+            ..Default::default()
+        },
+        Event::NewReward(new_reward_txn) => EventFields {
+            kind: "new_reward",
+            signature: new_reward_txn.signature.clone(),
+            farm_id: new_reward_txn.farm_id.clone(),
+            user: new_reward_txn.user.clone(),
+            lp_mint: new_reward_txn.lp_mint.clone(),
+            start_time: new_reward_txn.start_time,
+            end_time: new_reward_txn.end_time,
+            ..Default::default()
+        },
+        Event::Deposit(deposit_txn) => EventFields {
+            kind: "deposit",
+            signature: deposit_txn.signature.clone(),
+            farm_id: deposit_txn.farm_id.clone(),
+            user: deposit_txn.user.clone(),
+            lp_mint: deposit_txn.lp_mint.clone(),
+            lp_amount: deposit_txn.lp_amount,
+            ..Default::default()
+        },
+        Event::Withdraw(withdraw_txn) => EventFields {
+            kind: "withdraw",
+            signature: withdraw_txn.signature.clone(),
+            farm_id: withdraw_txn.farm_id.clone(),
+            user: withdraw_txn.user.clone(),
+            lp_mint: withdraw_txn.lp_mint.clone(),
+            lp_amount: withdraw_txn.lp_amount,
+            ..Default::default()
+        },
+        Event::Harvest(harvest_txn) => EventFields {
+            kind: "harvest",
+            signature: harvest_txn.signature.clone(),
+            farm_id: harvest_txn.farm_id.clone(),
+            user: harvest_txn.user.clone(),
+            ..Default::default()
+        },
+        Event::CreatorWithdrawReward(withdraw_reward_txn) => EventFields {
+            kind: "creator_withdraw_reward",
+            signature: withdraw_reward_txn.signature.clone(),
+            farm_id: withdraw_reward_txn.farm_id.clone(),
+            user: withdraw_reward_txn.user.clone(),
+            ..Default::default()
+        },
+    }
+}
+
+// the restart instruction split by `map_enriched_farm_txns`, "restart_or_add" when the reward's
+// previous schedule is not known
+fn restart_or_add_kind(update_kind: RewardUpdateKind) -> &'static str {
+    match update_kind {
+        RewardUpdateKind::Restart => "restart",
+        RewardUpdateKind::Extend => "extend",
+        RewardUpdateKind::Unknown => "restart_or_add",
+    }
+}
+
+// A reward schedule set by an event or a reward paid out by it
+#[derive(Debug, Default)]
+pub struct RewardFields {
+    pub reward_vault: String,
+    pub reward_mint: String,
+    pub reward_decimals: u32,
+    pub reward_amount: u64,
+    pub reward_per_second: u64,
+    pub reward_start_time: u32,
+    pub reward_end_time: u32,
+}
+
+impl From<&RewardSchedule> for RewardFields {
+    fn from(reward: &RewardSchedule) -> Self {
+        RewardFields {
+            reward_vault: reward.reward_vault.clone(),
+            reward_mint: reward.reward_mint.clone(),
+            reward_decimals: reward.reward_decimals,
+            reward_per_second: reward.reward_per_second,
+            reward_start_time: reward.start_time,
+            reward_end_time: reward.end_time,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&RewardPayout> for RewardFields {
+    fn from(payout: &RewardPayout) -> Self {
+        RewardFields {
+            reward_vault: payout.reward_vault.clone(),
+            reward_mint: payout.reward_mint.clone(),
+            reward_decimals: payout.reward_decimals,
+            reward_amount: payout.amount,
+            ..Default::default()
+        }
+    }
+}
+
+pub fn event_rewards(event: &Event) -> Vec<RewardFields> {
+    match event {
+        Event::Initialize(initialize_txn) => {
+            initialize_txn.rewards.iter().map(Into::into).collect()
+        }
+        Event::RestartOrAdd(restart_or_add_txn) => {
+            restart_or_add_txn.rewards.iter().map(Into::into).collect()
+        }
+        Event::NewReward(new_reward_txn) => new_reward_txn.rewards.iter().map(Into::into).collect(),
+        Event::Deposit(deposit_txn) => deposit_txn.rewards.iter().map(Into::into).collect(),
+        Event::Withdraw(withdraw_txn) => withdraw_txn.rewards.iter().map(Into::into).collect(),
+        Event::Harvest(harvest_txn) => harvest_txn.rewards.iter().map(Into::into).collect(),
+        Event::CreatorWithdrawReward(withdraw_reward_txn) => {
+            withdraw_reward_txn.reward.iter().map(Into::into).collect()
+        }
+    }
+}
+
+// rows of the rewards of an event are keyed by {event id}/{reward_position}
+pub fn event_reward_id(event_id: &String, reward_position: usize) -> String {
+    format!("{}/{}", event_id, reward_position)
+}
+
+pub fn status_name(farm_txn: &RaydiumFarmTransaction) -> &'static str {
+    if farm_txn.status == TransactionStatus::Failed as i32 {
+        "failed"
+    } else {
+        "success"
+    }
+}
+
+pub fn farm_event_rows(tables: &mut Tables, farm_txns: &RaydiumEcoFarmTransactions) {
+    for farm_txn in farm_txns.transactions.iter() {
+        let fields = match farm_txn.event.as_ref() {
+            Some(event) => event_fields(event),
+            None => continue,
+        };
+
+        let row = tables.create_row("farm_events", &farm_txn.id);
+        row.set("kind", fields.kind)
+            .set("signature", fields.signature)
+            .set("farm_id", fields.farm_id)
+            .set("user_address", fields.user)
+            .set("fee_payer", &farm_txn.fee_payer)
+            .set("lp_mint", fields.lp_mint)
+            .set("lp_amount", fields.lp_amount)
+            .set("start_time", fields.start_time)
+            .set("end_time", fields.end_time)
+            .set("slot", farm_txn.slot)
+            .set("block_time", farm_txn.block_time)
+            .set("transaction_index", farm_txn.transaction_index)
+            .set("instruction_index", farm_txn.instruction_index)
+            .set("ordinal", farm_txn.ordinal)
+            .set("status", status_name(farm_txn))
+            .set("error", &farm_txn.error);
+        // left null for top-level invocations
+        if let Some(inner_instruction_index) = farm_txn.inner_instruction_index {
+            row.set("inner_instruction_index", inner_instruction_index);
+        }
+    }
+}
+
+// the reward schedules set by an event, or the rewards paid out by it
+pub fn farm_event_reward_rows(tables: &mut Tables, farm_txns: &RaydiumEcoFarmTransactions) {
+    for farm_txn in farm_txns.transactions.iter() {
+        let rewards = match farm_txn.event.as_ref() {
+            Some(event) => event_rewards(event),
+            None => continue,
+        };
+
+        for (reward_position, reward) in rewards.into_iter().enumerate() {
+            tables
+                .create_row(
+                    "farm_event_rewards",
+                    event_reward_id(&farm_txn.id, reward_position),
+                )
+                .set("event_id", &farm_txn.id)
+                .set("reward_position", reward_position as u32)
+                .set("reward_vault", reward.reward_vault)
+                .set("reward_mint", reward.reward_mint)
+                .set("reward_decimals", reward.reward_decimals)
+                .set("reward_amount", reward.reward_amount)
+                .set("reward_per_second", reward.reward_per_second)
+                .set("start_time", reward.reward_start_time)
+                .set("end_time", reward.reward_end_time);
+        }
+    }
+}
+
+pub fn farm_rows(tables: &mut Tables, farm_deltas: &Deltas<DeltaProto<Farm>>) {
+    for delta in farm_deltas.deltas.iter() {
+        let farm = &delta.new_value;
+        match keys::parse_farm_key(&delta.key) {
+            Some(FarmKey::Farm(farm_id)) => {
+                table_row(tables, &delta.operation, "farms", farm_id)
+                    .set("lp_mint", &farm.lp_mint)
+                    .set("creator", &farm.creator)
+                    .set("creation_slot", farm.creation_slot)
+                    .set("creation_signature", &farm.creation_signature)
+                    .set("updated_slot", farm.updated_slot);
+            }
+            Some(FarmKey::Reward(farm_id, reward_vault)) => {
+                for reward in farm.rewards.iter() {
+                    table_row(tables, &delta.operation, "reward_schedules", &delta.key)
+                        .set("farm_id", farm_id)
+                        .set("reward_vault", reward_vault)
+                        .set("reward_mint", &reward.reward_mint)
+                        .set("reward_decimals", reward.reward_decimals)
+                        .set("reward_per_second", reward.reward_per_second)
+                        .set("start_time", reward.start_time)
+                        .set("end_time", reward.end_time)
+                        .set("updated_slot", farm.updated_slot);
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+fn table_row<'a>(
+    tables: &'a mut Tables,
+    operation: &Operation,
+    table: &str,
+    key: &str,
+) -> &'a mut Row {
+    if *operation == Operation::Create {
+        tables.create_row(table, key)
+    } else {
+        tables.update_row(table, key)
+    }
+}
+
+pub fn position_rows(tables: &mut Tables, position_changes: &PositionChanges) {
+    for position in position_changes.positions.iter() {
+        let key = keys::position_key(&position.farm_id, &position.user);
+        let row = if position.opened {
+            tables.create_row("positions", key)
+        } else {
+            tables.update_row("positions", key)
+        };
+        row.set("farm_id", &position.farm_id)
+            .set("user_address", &position.user)
+            .set("staked", &position.staked)
+            .set("first_deposit_slot", position.first_deposit_slot)
+            .set("last_activity_slot", position.last_activity_slot);
+    }
+}
+
+// One row per reward of an event, or a single row without reward columns for an event without
+// rewards
+pub fn flat_event_rows(tables: &mut Tables, farm_txns: &RaydiumEcoFarmTransactions) {
+    for farm_txn in farm_txns.transactions.iter() {
+        let event = match farm_txn.event.as_ref() {
+            Some(event) => event,
+            None => continue,
+        };
+        let fields = event_fields(event);
+        let mut rewards = event_rewards(event);
+        if rewards.is_empty() {
+            rewards.push(RewardFields::default());
+        }
+
+        for (reward_position, reward) in rewards.into_iter().enumerate() {
+            tables
+                .create_row(
+                    "farm_events_flat",
+                    event_reward_id(&farm_txn.id, reward_position),
+                )
+                .set("event_id", &farm_txn.id)
+                .set("reward_position", reward_position as u32)
+                .set("kind", fields.kind)
+                .set("signature", &fields.signature)
+                .set("slot", farm_txn.slot)
+                .set("block_time", farm_txn.block_time)
+                .set("transaction_index", farm_txn.transaction_index)
+                .set("instruction_index", farm_txn.instruction_index)
+                .set(
+                    "inner_instruction_index",
+                    farm_txn
+                        .inner_instruction_index
+                        .map(|inner_instruction_index| inner_instruction_index as i64)
+                        .unwrap_or(-1),
+                )
+                .set("ordinal", farm_txn.ordinal)
+                .set("status", status_name(farm_txn))
+                .set("error", &farm_txn.error)
+                .set("farm_id", &fields.farm_id)
+                .set("user_address", &fields.user)
+                .set("fee_payer", &farm_txn.fee_payer)
+                .set("lp_mint", &fields.lp_mint)
+                .set("lp_amount", fields.lp_amount)
+                .set("start_time", fields.start_time)
+                .set("end_time", fields.end_time)
+                .set("reward_vault", reward.reward_vault)
+                .set("reward_mint", reward.reward_mint)
+                .set("reward_decimals", reward.reward_decimals)
+                .set("reward_amount", reward.reward_amount)
+                .set("reward_per_second", reward.reward_per_second)
+                .set("reward_start_time", reward.reward_start_time)
+                .set("reward_end_time", reward.reward_end_time);
+        }
+    }
+}
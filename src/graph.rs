@@ -0,0 +1,131 @@
+// Entities of the `graph_out` subgraph sink, defined in schema.graphql.
+
+use substreams::pb::substreams::store_delta::Operation;
+use substreams::scalar::BigInt;
+use substreams::store::{DeltaProto, Deltas};
+use substreams_entity_change::tables::{Row, Tables};
+
+use crate::db;
+use crate::keys::{self, FarmKey};
+use crate::pb::raydium_eco_farms::{Farm, PositionChanges, RaydiumEcoFarmTransactions};
+
+pub fn farm_entities(tables: &mut Tables, farm_deltas: &Deltas<DeltaProto<Farm>>) {
+    for delta in farm_deltas.deltas.iter() {
+        let farm = &delta.new_value;
+        match keys::parse_farm_key(&delta.key) {
+            Some(FarmKey::Farm(farm_id)) => {
+                entity_row(tables, &delta.operation, "Farm", farm_id)
+                    .set("lpMint", &farm.lp_mint)
+                    .set("creator", &farm.creator)
+                    .set("creationSlot", BigInt::from(farm.creation_slot))
+                    .set("creationSignature", &farm.creation_signature)
+                    .set("updatedSlot", BigInt::from(farm.updated_slot));
+            }
+            Some(FarmKey::Reward(farm_id, reward_vault)) => {
+                for reward in farm.rewards.iter() {
+                    entity_row(tables, &delta.operation, "RewardSchedule", &delta.key)
+                        .set("farm", farm_id)
+                        .set("rewardVault", reward_vault)
+                        .set("rewardMint", &reward.reward_mint)
+                        .set("rewardDecimals", reward.reward_decimals as i32)
+                        .set("rewardPerSecond", BigInt::from(reward.reward_per_second))
+                        .set("startTime", BigInt::from(reward.start_time))
+                        .set("endTime", BigInt::from(reward.end_time))
+                        .set("updatedSlot", BigInt::from(farm.updated_slot));
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+fn entity_row<'a>(
+    tables: &'a mut Tables,
+    operation: &Operation,
+    entity: &str,
+    id: &str,
+) -> &'a mut Row {
+    if *operation == Operation::Create {
+        tables.create_row(entity, id)
+    } else {
+        tables.update_row(entity, id)
+    }
+}
+
+// events are immutable, a re-processed range creates the same ids
+pub fn farm_event_entities(tables: &mut Tables, farm_txns: &RaydiumEcoFarmTransactions) {
+    for farm_txn in farm_txns.transactions.iter() {
+        let event = match farm_txn.event.as_ref() {
+            Some(event) => event,
+            None => continue,
+        };
+        let fields = db::event_fields(event);
+
+        let row = tables.create_row("FarmEvent", &farm_txn.id);
+        row.set("kind", fields.kind)
+            .set("signature", fields.signature)
+            .set("farm", fields.farm_id)
+            .set("user", fields.user)
+            .set("feePayer", &farm_txn.fee_payer)
+            .set("lpMint", fields.lp_mint)
+            .set("lpAmount", BigInt::from(fields.lp_amount))
+            .set("startTime", BigInt::from(fields.start_time))
+            .set("endTime", BigInt::from(fields.end_time))
+            .set("slot", BigInt::from(farm_txn.slot))
+            .set("blockTime", BigInt::from(farm_txn.block_time))
+            .set("transactionIndex", farm_txn.transaction_index as i32)
+            .set("instructionIndex", farm_txn.instruction_index as i32)
+            .set("ordinal", farm_txn.ordinal as i32)
+            .set("status", db::status_name(farm_txn))
+            .set("error", &farm_txn.error);
+        if let Some(inner_instruction_index) = farm_txn.inner_instruction_index {
+            row.set("innerInstructionIndex", inner_instruction_index as i32);
+        }
+
+        for (reward_position, reward) in db::event_rewards(event).into_iter().enumerate() {
+            tables
+                .create_row(
+                    "FarmEventReward",
+                    db::event_reward_id(&farm_txn.id, reward_position),
+                )
+                .set("event", &farm_txn.id)
+                .set("rewardPosition", reward_position as i32)
+                .set("rewardVault", reward.reward_vault)
+                .set("rewardMint", reward.reward_mint)
+                .set("rewardDecimals", reward.reward_decimals as i32)
+                .set("rewardAmount", BigInt::from(reward.reward_amount))
+                .set("rewardPerSecond", BigInt::from(reward.reward_per_second))
+                .set("startTime", BigInt::from(reward.reward_start_time))
+                .set("endTime", BigInt::from(reward.reward_end_time));
+        }
+    }
+}
+
+pub fn position_entities(tables: &mut Tables, position_changes: &PositionChanges) {
+    for position in position_changes.positions.iter() {
+        // every change carries the whole position
+        let id = keys::position_key(&position.farm_id, &position.user);
+        let row = if position.opened {
+            tables.create_row("UserPosition", id)
+        } else {
+            tables.update_row("UserPosition", id)
+        };
+        row.set("farm", &position.farm_id)
+            .set("user", &position.user)
+            .set(
+                "staked",
+                position
+                    .staked
+                    .parse::<BigInt>()
+                    .unwrap_or_else(|_| BigInt::zero()),
+            )
+            .set(
+                "firstDepositSlot",
+                BigInt::from(position.first_deposit_slot),
+            )
+            .set(
+                "lastActivitySlot",
+                BigInt::from(position.last_activity_slot),
+            );
+    }
+}
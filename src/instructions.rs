@@ -0,0 +1,417 @@
+// Typed decoder for the farm program's instruction data.
+//
+// The farm program (Raydium farm v6) is not an Anchor program: the first byte of the
+// instruction data is the instruction tag, followed by the little endian arguments.
+// Layouts follow the Raydium SDK:
+//
+//   0 - create farm:    tag, nonce: u64, [is_set, reward_per_second, open_time, end_time, reward_type: u64; n]
+//   1 - deposit:        tag, amount: u64
+//   2 - withdraw:       tag, amount: u64
+//   3 - restart reward: tag, open_time: u64, end_time: u64, reward_per_second: u64
+//   4 - add reward:     tag, is_set: u64, reward_per_second: u64, open_time: u64, end_time: u64, reward_type: u64
+//   5 - creator withdraw reward: tag (the creator takes back what is left in an ended reward's vault)
+
+use crate::pb::sf::solana::r#type::v1::{CompiledInstruction, InnerInstruction, InnerInstructions};
+
+pub const CREATE_FARM: u8 = 0;
+pub const DEPOSIT: u8 = 1;
+pub const WITHDRAW: u8 = 2;
+pub const RESTART_REWARD: u8 = 3;
+pub const ADD_REWARD: u8 = 4;
+pub const CREATOR_WITHDRAW_REWARD: u8 = 5;
+
+// Positions in each instruction's own account list
+pub const CREATE_FARM_FARM_ACCOUNT: usize = 3;
+pub const CREATE_FARM_LP_VAULT_ACCOUNT: usize = 5;
+pub const CREATE_FARM_LP_MINT_ACCOUNT: usize = 6;
+pub const CREATE_FARM_OWNER_ACCOUNT: usize = 10;
+pub const RESTART_REWARD_FARM_ACCOUNT: usize = 1;
+pub const RESTART_REWARD_VAULT_ACCOUNT: usize = 3;
+pub const RESTART_REWARD_OWNER_ACCOUNT: usize = 5;
+pub const ADD_REWARD_FARM_ACCOUNT: usize = 3;
+pub const ADD_REWARD_MINT_ACCOUNT: usize = 5;
+pub const ADD_REWARD_VAULT_ACCOUNT: usize = 6;
+pub const ADD_REWARD_OWNER_ACCOUNT: usize = 8;
+pub const CREATOR_WITHDRAW_REWARD_FARM_ACCOUNT: usize = 1;
+pub const CREATOR_WITHDRAW_REWARD_VAULT_ACCOUNT: usize = 3;
+pub const CREATOR_WITHDRAW_REWARD_OWNER_ACCOUNT: usize = 5;
+
+pub const DEPOSIT_FARM_ACCOUNT: usize = 2;
+pub const DEPOSIT_LP_VAULT_ACCOUNT: usize = 4;
+pub const DEPOSIT_LEDGER_ACCOUNT: usize = 5;
+pub const DEPOSIT_OWNER_ACCOUNT: usize = 6;
+pub const DEPOSIT_USER_LP_ACCOUNT: usize = 7;
+pub const WITHDRAW_FARM_ACCOUNT: usize = 1;
+pub const WITHDRAW_LP_VAULT_ACCOUNT: usize = 3;
+pub const WITHDRAW_LEDGER_ACCOUNT: usize = 4;
+pub const WITHDRAW_OWNER_ACCOUNT: usize = 5;
+pub const WITHDRAW_USER_LP_ACCOUNT: usize = 6;
+// deposit and withdraw list (reward vault, user reward token account) for every farm reward after
+// their fixed accounts
+pub const DEPOSIT_REWARD_ACCOUNTS_START: usize = 8;
+pub const WITHDRAW_REWARD_ACCOUNTS_START: usize = 7;
+
+// create farm lists (reward mint, reward vault, creator reward token account) for every reward
+// after its 11 fixed accounts
+const CREATE_FARM_REWARD_ACCOUNTS_START: usize = 11;
+const CREATE_FARM_ACCOUNTS_PER_REWARD: usize = 3;
+
+pub fn create_farm_reward_mint_account(reward_index: usize) -> usize {
+    CREATE_FARM_REWARD_ACCOUNTS_START + reward_index * CREATE_FARM_ACCOUNTS_PER_REWARD
+}
+
+pub fn create_farm_reward_vault_account(reward_index: usize) -> usize {
+    CREATE_FARM_REWARD_ACCOUNTS_START + reward_index * CREATE_FARM_ACCOUNTS_PER_REWARD + 1
+}
+
+const REWARD_TIME_INFO_LEN: usize = 40;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RewardTimeInfo {
+    pub is_set: u64,
+    pub reward_per_second: u64,
+    pub open_time: u64,
+    pub end_time: u64,
+    pub reward_type: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FarmInstruction {
+    Create {
+        nonce: u64,
+        rewards: Vec<RewardTimeInfo>,
+    },
+    Deposit {
+        amount: u64,
+    },
+    Withdraw {
+        amount: u64,
+    },
+    RestartReward {
+        open_time: u64,
+        end_time: u64,
+        reward_per_second: u64,
+    },
+    AddReward(RewardTimeInfo),
+    CreatorWithdrawReward,
+    Unknown(u8),
+}
+
+pub fn decode(data: &[u8]) -> Result<FarmInstruction, String> {
+    let (tag, args) = data.split_first().ok_or("Empty instruction data")?;
+
+    match *tag {
+        CREATE_FARM => {
+            let nonce = read_u64(args, 0)?;
+            let reward_data = &args[8..];
+            if reward_data.len() % REWARD_TIME_INFO_LEN != 0 {
+                return Err(format!(
+                    "Invalid create farm reward data length: {}",
+                    reward_data.len()
+                ));
+            }
+            let rewards = reward_data
+                .chunks(REWARD_TIME_INFO_LEN)
+                .map(read_reward_time_info)
+                .collect::<Result<Vec<RewardTimeInfo>, String>>()?;
+
+            Ok(FarmInstruction::Create { nonce, rewards })
+        }
+        DEPOSIT => Ok(FarmInstruction::Deposit {
+            amount: read_u64(args, 0)?,
+        }),
+        WITHDRAW => Ok(FarmInstruction::Withdraw {
+            amount: read_u64(args, 0)?,
+        }),
+        RESTART_REWARD => Ok(FarmInstruction::RestartReward {
+            open_time: read_u64(args, 0)?,
+            end_time: read_u64(args, 8)?,
+            reward_per_second: read_u64(args, 16)?,
+        }),
+        ADD_REWARD => Ok(FarmInstruction::AddReward(read_reward_time_info(args)?)),
+        CREATOR_WITHDRAW_REWARD => Ok(FarmInstruction::CreatorWithdrawReward),
+        other => Ok(FarmInstruction::Unknown(other)),
+    }
+}
+
+fn read_reward_time_info(data: &[u8]) -> Result<RewardTimeInfo, String> {
+    Ok(RewardTimeInfo {
+        is_set: read_u64(data, 0)?,
+        reward_per_second: read_u64(data, 8)?,
+        open_time: read_u64(data, 16)?,
+        end_time: read_u64(data, 24)?,
+        reward_type: read_u64(data, 32)?,
+    })
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64, String> {
+    let bytes = data.get(offset..offset + 8).ok_or(format!(
+        "Instruction data too short to read u64 at {}",
+        offset
+    ))?;
+
+    Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+// A single invocation of the farm program, either as a top-level instruction or through a CPI
+#[derive(Debug)]
+pub struct FarmInvocation<'a> {
+    pub instruction_index: u32,
+    pub inner_instruction_index: Option<u32>,
+    pub stack_height: u32,
+    pub accounts: &'a Vec<u8>,
+    pub data: &'a Vec<u8>,
+    // instructions the farm program invoked through CPI (token transfers, ...)
+    pub inner_instructions: Vec<&'a InnerInstruction>,
+}
+
+impl<'a> FarmInvocation<'a> {
+    // Resolves the account at `position` in this instruction's account list
+    pub fn account(&self, accounts: &[String], position: usize) -> Result<String, String> {
+        let index = self
+            .accounts
+            .get(position)
+            .ok_or(format!("Instruction account {} not found", position))?;
+
+        accounts
+            .get(*index as usize)
+            .cloned()
+            .ok_or(format!("Account at index {} not found", index))
+    }
+}
+
+// Collects every farm program invocation of a transaction, in execution order
+pub fn farm_invocations<'a>(
+    compiled_instructions: &'a [CompiledInstruction],
+    inner_instructions: &'a [InnerInstructions],
+    farm_program_index: u32,
+) -> Vec<FarmInvocation<'a>> {
+    let mut invocations = vec![];
+
+    for (instruction_index, instruction) in compiled_instructions.iter().enumerate() {
+        let inner = inner_instructions
+            .iter()
+            .filter(|inner| inner.index == instruction_index as u32)
+            .flat_map(|inner| inner.instructions.iter())
+            .collect::<Vec<&InnerInstruction>>();
+
+        if instruction.program_id_index == farm_program_index {
+            invocations.push(FarmInvocation {
+                instruction_index: instruction_index as u32,
+                inner_instruction_index: None,
+                stack_height: 1,
+                accounts: &instruction.accounts,
+                data: &instruction.data,
+                inner_instructions: inner.clone(),
+            });
+        }
+
+        for (inner_instruction_index, inner_instruction) in inner.iter().enumerate() {
+            if inner_instruction.program_id_index == farm_program_index {
+                invocations.push(FarmInvocation {
+                    instruction_index: instruction_index as u32,
+                    inner_instruction_index: Some(inner_instruction_index as u32),
+                    // older blocks don't record the stack height, a CPI is at least at height 2
+                    stack_height: inner_instruction.stack_height.unwrap_or(2),
+                    accounts: &inner_instruction.accounts,
+                    data: &inner_instruction.data,
+                    inner_instructions: nested_calls(
+                        &inner[inner_instruction_index + 1..],
+                        inner_instruction,
+                    ),
+                });
+            }
+        }
+    }
+
+    invocations
+}
+
+// The instructions following a CPI with a greater stack height are the calls it made. Without
+// stack heights (older blocks) they can't be told apart from the caller's next calls.
+fn nested_calls<'a>(
+    following: &[&'a InnerInstruction],
+    caller: &InnerInstruction,
+) -> Vec<&'a InnerInstruction> {
+    let caller_height = match caller.stack_height {
+        Some(stack_height) => stack_height,
+        None => return vec![],
+    };
+
+    following
+        .iter()
+        .take_while(|inner| {
+            inner
+                .stack_height
+                .map(|stack_height| stack_height > caller_height)
+                .unwrap_or(false)
+        })
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instruction_data(tag: u8, args: &[u64]) -> Vec<u8> {
+        let mut data = vec![tag];
+        for arg in args {
+            data.extend_from_slice(&arg.to_le_bytes());
+        }
+        data
+    }
+
+    // the two rewards logged by the Init of the sample create farm blocks
+    fn create_farm_rewards() -> Vec<RewardTimeInfo> {
+        vec![
+            RewardTimeInfo {
+                is_set: 1,
+                reward_per_second: 1653,
+                open_time: 1737491275,
+                end_time: 1738096075,
+                reward_type: 0,
+            },
+            RewardTimeInfo {
+                is_set: 1,
+                reward_per_second: 3,
+                open_time: 1737491287,
+                end_time: 1738096087,
+                reward_type: 0,
+            },
+        ]
+    }
+
+    #[test]
+    fn decode_create_farm() {
+        let data = instruction_data(
+            CREATE_FARM,
+            &[
+                254, 1, 1653, 1737491275, 1738096075, 0, 1, 3, 1737491287, 1738096087, 0,
+            ],
+        );
+
+        assert_eq!(
+            decode(&data),
+            Ok(FarmInstruction::Create {
+                nonce: 254,
+                rewards: create_farm_rewards(),
+            })
+        );
+    }
+
+    #[test]
+    fn decode_create_farm_without_rewards() {
+        assert_eq!(
+            decode(&instruction_data(CREATE_FARM, &[254])),
+            Ok(FarmInstruction::Create {
+                nonce: 254,
+                rewards: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn decode_create_farm_with_partial_reward() {
+        let mut data = instruction_data(CREATE_FARM, &[254, 1, 1653, 1737491275, 1738096075, 0]);
+        data.pop();
+
+        assert_eq!(
+            decode(&data),
+            Err("Invalid create farm reward data length: 39".to_string())
+        );
+    }
+
+    #[test]
+    fn decode_deposit_and_withdraw() {
+        assert_eq!(
+            decode(&instruction_data(DEPOSIT, &[1_000_000])),
+            Ok(FarmInstruction::Deposit { amount: 1_000_000 })
+        );
+        assert_eq!(
+            decode(&instruction_data(WITHDRAW, &[0])),
+            Ok(FarmInstruction::Withdraw { amount: 0 })
+        );
+    }
+
+    #[test]
+    fn decode_restart_reward() {
+        // "process_creator_restart: EVfHjrgu9KFV4889AdyBNtB7jgBhAaPZeSAJ9sY163vD, 1740777211, 1741382011, 16"
+        assert_eq!(
+            decode(&instruction_data(
+                RESTART_REWARD,
+                &[1740777211, 1741382011, 16]
+            )),
+            Ok(FarmInstruction::RestartReward {
+                open_time: 1740777211,
+                end_time: 1741382011,
+                reward_per_second: 16,
+            })
+        );
+    }
+
+    #[test]
+    fn decode_add_reward() {
+        // "process_admin_add_reward_token: 6npFrUXvt7yniYerAwcBjg5SKspxN4tZbGFxEqMFEZHJ, 1740785220, 1741390020, 1, 0"
+        assert_eq!(
+            decode(&instruction_data(
+                ADD_REWARD,
+                &[1, 1, 1740785220, 1741390020, 0]
+            )),
+            Ok(FarmInstruction::AddReward(RewardTimeInfo {
+                is_set: 1,
+                reward_per_second: 1,
+                open_time: 1740785220,
+                end_time: 1741390020,
+                reward_type: 0,
+            }))
+        );
+    }
+
+    #[test]
+    fn decode_creator_withdraw_reward() {
+        assert_eq!(
+            decode(&[CREATOR_WITHDRAW_REWARD]),
+            Ok(FarmInstruction::CreatorWithdrawReward)
+        );
+    }
+
+    #[test]
+    fn decode_unknown_tag() {
+        assert_eq!(
+            decode(&instruction_data(12, &[1])),
+            Ok(FarmInstruction::Unknown(12))
+        );
+    }
+
+    #[test]
+    fn decode_empty_data() {
+        assert_eq!(decode(&[]), Err("Empty instruction data".to_string()));
+    }
+
+    #[test]
+    fn decode_truncated_data() {
+        for tag in [CREATE_FARM, DEPOSIT, WITHDRAW, RESTART_REWARD, ADD_REWARD] {
+            assert_eq!(
+                decode(&[tag]),
+                Err("Instruction data too short to read u64 at 0".to_string()),
+                "tag {}",
+                tag
+            );
+        }
+
+        let mut restart = instruction_data(RESTART_REWARD, &[1740777211, 1741382011, 16]);
+        restart.truncate(20);
+        assert_eq!(
+            decode(&restart),
+            Err("Instruction data too short to read u64 at 16".to_string())
+        );
+
+        let mut add_reward = instruction_data(ADD_REWARD, &[1, 1, 1740785220, 1741390020, 0]);
+        add_reward.pop();
+        assert_eq!(
+            decode(&add_reward),
+            Err("Instruction data too short to read u64 at 32".to_string())
+        );
+    }
+}
@@ -0,0 +1,114 @@
+// Store keys.
+//
+// store_farms:
+//   farm:{farm_id}                        Farm as created by Init (LP mint, creator, creation slot and signature),
+//                                         without rewards
+//   farm:{farm_id}:reward:{reward_vault}  Farm holding the current schedule of that single reward, set by
+//                                         Init, RestartOrAdd and NewReward
+//
+// store_farm_index:
+//   farms                                 every farm:{farm_id}:reward:{reward_vault} key of store_farms
+//   rewards:{farm_id}                     the farm:{farm_id}:reward:{reward_vault} keys of that farm, the
+//                                         farm's current schedule is read from them
+//
+// store_schedule_buckets:
+//   start:{minute}                        farm:{farm_id}:reward:{reward_vault} keys of the rewards starting in
+//                                         that minute (start_time / 60)
+//   end:{minute}                          same for the rewards ending in that minute
+//
+// store_farm_states:
+//   farm:{farm_id}                        FarmLifecycle holding the farm's current state
+//
+// store_farm_tvl:
+//   tvl:{farm_id}                         LP amount staked in the farm
+//
+// store_position_staked, store_position_first_deposit, store_position_last_activity:
+//   {farm_id}:{user}                      LP amount staked by the user, slot of their first deposit and of
+//                                         their last deposit, withdraw or harvest
+//
+// store_block_time:
+//   block_time                            timestamp of the last block
+//
+// A restart leaves the reward in the buckets of its previous schedule, readers check the bucket
+// against the reward's current schedule in store_farms.
+//
+// The reward vault identifies a reward: restart only passes the vault, and its position in the
+// instruction is not the farm's reward slot.
+
+pub const FARM_INDEX_KEY: &str = "farms";
+pub const BLOCK_TIME_KEY: &str = "block_time";
+
+pub const SCHEDULE_BUCKET_SECONDS: i64 = 60;
+
+pub fn schedule_start_key(minute: i64) -> String {
+    format!("start:{}", minute)
+}
+
+pub fn schedule_end_key(minute: i64) -> String {
+    format!("end:{}", minute)
+}
+
+pub fn farm_tvl_key(farm_id: &String) -> String {
+    format!("tvl:{}", farm_id)
+}
+
+pub fn farm_id_from_tvl_key(key: &str) -> Option<&str> {
+    key.strip_prefix("tvl:")
+}
+
+pub fn position_key(farm_id: &String, user: &String) -> String {
+    format!("{}:{}", farm_id, user)
+}
+
+// (farm_id, user) of a position key
+pub fn position_from_key(key: &str) -> Option<(&str, &str)> {
+    key.split_once(':')
+}
+
+pub fn farm_key(farm_id: &String) -> String {
+    format!("farm:{}", farm_id)
+}
+
+pub fn farm_rewards_index_key(farm_id: &String) -> String {
+    format!("rewards:{}", farm_id)
+}
+
+pub fn farm_reward_key(farm_id: &String, reward_vault: &String) -> String {
+    format!("farm:{}:reward:{}", farm_id, reward_vault)
+}
+
+// a store_farms key
+pub enum FarmKey<'a> {
+    Farm(&'a str),
+    // (farm_id, reward_vault)
+    Reward(&'a str, &'a str),
+}
+
+pub fn parse_farm_key(key: &str) -> Option<FarmKey<'_>> {
+    let farm_key = key.strip_prefix("farm:")?;
+    match farm_key.split_once(":reward:") {
+        Some((farm_id, reward_vault)) => Some(FarmKey::Reward(farm_id, reward_vault)),
+        None => Some(FarmKey::Farm(farm_key)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_farm_keys() {
+        let farm_id = "DPs4dvoGiBV9cPxKD73UXw9YbKJf4XvxuDHJJ8wQpf6e".to_string();
+        let reward_vault = "EVfHjrgu9KFV4889AdyBNtB7jgBhAaPZeSAJ9sY163vD".to_string();
+
+        assert!(matches!(
+            parse_farm_key(&farm_key(&farm_id)),
+            Some(FarmKey::Farm(id)) if id == farm_id
+        ));
+        assert!(matches!(
+            parse_farm_key(&farm_reward_key(&farm_id, &reward_vault)),
+            Some(FarmKey::Reward(id, vault)) if id == farm_id && vault == reward_vault
+        ));
+        assert!(parse_farm_key(&farm_tvl_key(&farm_id)).is_none());
+    }
+}
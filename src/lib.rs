@@ -1,102 +1,1676 @@
+mod db;
+mod graph;
+mod instructions;
+mod keys;
 pub mod pb;
+mod tokens;
+mod transaction_error;
 
+use std::collections::{HashMap, HashSet};
+
+use instructions::{FarmInstruction, FarmInvocation, RewardTimeInfo};
 use pb::{
     raydium_eco_farms::{
-        raydium_farm_transaction::Event, InitializeTransaction, NewRewardTransaction,
-        RaydiumEcoFarmTransactions, RaydiumFarmTransaction, RestartOrAddTransaction,
+        raydium_farm_transaction::Event, ActiveFarm, ActiveFarms, CreatorWithdrawRewardTransaction,
+        DepositTransaction, Farm, FarmEnded, FarmLifecycle, FarmScheduleEvents, FarmStarted,
+        FarmState, FarmStateTransition, FarmStateTransitions, FarmTvl, FarmTvls,
+        HarvestTransaction, InitializeTransaction, LpMintSource, NewRewardTransaction,
+        PositionChange, PositionChanges, RaydiumEcoFarmTransactions, RaydiumFarmTransaction,
+        RestartOrAddTransaction, RewardPayout, RewardSchedule, RewardUpdateKind,
+        TransactionDiagnostic, TransactionStatus, WithdrawTransaction,
     },
-    sf::substreams::solana::v1::Transactions,
+    sf::solana::r#type::v1::{Block, ConfirmedTransaction},
 };
 
 use substreams::log::println;
+use substreams::pb::substreams::store_delta::Operation;
+use substreams::pb::substreams::Clock;
+use substreams::scalar::BigInt;
+use substreams::store::{
+    Appender, DeltaBigInt, DeltaInt64, DeltaProto, Deltas, StoreAdd, StoreAddBigInt, StoreAppend,
+    StoreGet, StoreGetArray, StoreGetBigInt, StoreGetInt64, StoreGetProto, StoreMax, StoreMaxInt64,
+    StoreMin, StoreMinInt64, StoreNew, StoreSet, StoreSetProto,
+};
+use substreams_database_change::pb::database::DatabaseChanges;
+use substreams_database_change::tables::Tables;
+use substreams_entity_change::pb::entity::EntityChanges;
+use substreams_entity_change::tables::Tables as EntityTables;
+use tokens::{TokenAccount, TokenTransfer};
 
 const FARM_PROGRAM_ID: &str = "FarmqiPv5eAj3j1GMdMCMUGXqPUvmquZtMy86QH6rzhG";
 
 #[substreams::handlers::map]
-fn map_farm_txns(transactions: Transactions) -> Result<Option<RaydiumEcoFarmTransactions>, String> {
-    let mut farm_transactions = RaydiumEcoFarmTransactions::default();
+fn map_farm_txns(
+    params: String,
+    block: Block,
+) -> Result<Option<RaydiumEcoFarmTransactions>, String> {
+    let include_failed = include_failed_transactions(&params)?;
+    let farm_program = bs58::decode(FARM_PROGRAM_ID)
+        .into_vec()
+        .map_err(|e| format!("Invalid farm program ID: {}", e))?;
+    let block_time = block
+        .block_time
+        .as_ref()
+        .map(|block_time| block_time.timestamp)
+        .unwrap_or_default();
+    let mut farm_transactions = RaydiumEcoFarmTransactions {
+        slot: block.slot,
+        block_hash: block.blockhash.clone(),
+        block_time,
+        ..Default::default()
+    };
 
-    for txn in transactions.transactions.iter() {
-        let meta_wrapped = &txn.meta;
-        let meta = meta_wrapped.as_ref().unwrap();
+    // the block filter only keeps blocks with a farm transaction, the other transactions of the
+    // block are skipped here so `transaction_index` stays the position in the block
+    for (transaction_index, txn) in block.transactions.iter().enumerate() {
+        if !references_program(txn, &farm_program) {
+            continue;
+        }
 
-        let txn_wrapped = &txn.transaction;
-        let transaction = txn_wrapped.as_ref().unwrap();
+        // a malformed or unexpected transaction is reported and skipped, it must not fail the block
+        match process_transaction(txn, include_failed) {
+            Ok(events) => {
+                for mut event in events {
+                    event.slot = block.slot;
+                    event.block_time = block_time;
+                    event.transaction_index = transaction_index as u32;
+                    event.ordinal = farm_transactions.transactions.len() as u32;
+                    farm_transactions.transactions.push(event);
+                }
+            }
+            Err(message) => {
+                println(format!("skipping transaction: {}", message));
+                farm_transactions.diagnostics.push(TransactionDiagnostic {
+                    signature: transaction_signature(txn).unwrap_or_default(),
+                    transaction_index: transaction_index as u32,
+                    message,
+                });
+            }
+        }
+    }
+    if farm_transactions.transactions.is_empty() && farm_transactions.diagnostics.is_empty() {
+        return Ok(None); // Early return with None
+    }
 
-        let message = transaction.message.as_ref().unwrap();
+    Ok(Some(farm_transactions))
+}
 
-        let log_messages = &meta.log_messages;
+// failed transactions did not change any farm or position
+fn successful_events(
+    farm_txns: &RaydiumEcoFarmTransactions,
+) -> impl Iterator<Item = &RaydiumFarmTransaction> {
+    farm_txns
+        .transactions
+        .iter()
+        .filter(|farm_txn| farm_txn.status != TransactionStatus::Failed as i32)
+}
 
-        log_messages.iter().for_each(|log| {
-            println(format!("log: {:?}", log));
-        });
+#[substreams::handlers::store]
+fn store_farms(farm_txns: RaydiumEcoFarmTransactions, store: StoreSetProto<Farm>) {
+    for farm_txn in successful_events(&farm_txns) {
+        let ordinal = farm_txn.ordinal as u64;
 
-        let signature = bs58::encode(transaction.signatures.get(0).unwrap()).into_string();
-        println(format!("signature: {:?}", signature));
+        let (farm_id, rewards) = match &farm_txn.event {
+            Some(Event::Initialize(initialize_txn)) => {
+                store.set(
+                    ordinal,
+                    keys::farm_key(&initialize_txn.farm_id),
+                    &Farm {
+                        farm_id: initialize_txn.farm_id.clone(),
+                        lp_mint: initialize_txn.lp_mint.clone(),
+                        creator: initialize_txn.user.clone(),
+                        creation_slot: farm_txn.slot,
+                        creation_signature: initialize_txn.signature.clone(),
+                        // the rewards are kept under their own keys, see `keys::farm_reward_key`
+                        rewards: vec![],
+                        updated_slot: farm_txn.slot,
+                    },
+                );
+                (&initialize_txn.farm_id, &initialize_txn.rewards)
+            }
+            Some(Event::RestartOrAdd(restart_or_add_txn)) => {
+                (&restart_or_add_txn.farm_id, &restart_or_add_txn.rewards)
+            }
+            Some(Event::NewReward(new_reward_txn)) => {
+                (&new_reward_txn.farm_id, &new_reward_txn.rewards)
+            }
+            _ => continue,
+        };
 
-        let compiled_instructions = &transaction.message.as_ref().unwrap().instructions;
+        for reward in rewards {
+            store.set(
+                ordinal,
+                keys::farm_reward_key(farm_id, &reward.reward_vault),
+                &Farm {
+                    farm_id: farm_id.clone(),
+                    rewards: vec![reward.clone()],
+                    updated_slot: farm_txn.slot,
+                    ..Default::default()
+                },
+            );
+        }
+    }
+}
 
-        let mut all_accounts = vec![];
-        all_accounts.extend(message.account_keys.iter());
-        all_accounts.extend(meta.loaded_writable_addresses.iter());
-        all_accounts.extend(meta.loaded_readonly_addresses.iter());
+// restart and add reward instructions don't pass the LP mint, it is taken from the farm's Init
+#[substreams::handlers::map]
+fn map_enriched_farm_txns(
+    farm_txns: RaydiumEcoFarmTransactions,
+    farms: StoreGetProto<Farm>,
+) -> Result<Option<RaydiumEcoFarmTransactions>, String> {
+    let mut enriched_txns = farm_txns;
 
-        //get all accounts in base58
-        let accounts = all_accounts
-            .iter()
-            .map(|account| bs58::encode(account).into_string())
-            .collect::<Vec<String>>();
+    for farm_txn in enriched_txns.transactions.iter_mut() {
+        let ordinal = farm_txn.ordinal as u64;
+        match farm_txn.event.as_mut() {
+            Some(Event::RestartOrAdd(restart_or_add_txn)) => {
+                if let Some(farm) =
+                    farms.get_at(ordinal, keys::farm_key(&restart_or_add_txn.farm_id))
+                {
+                    restart_or_add_txn.lp_mint = farm.lp_mint;
+                    restart_or_add_txn.creation_signature = farm.creation_signature;
+                } else {
+                    println(format!("farm not found: {}", restart_or_add_txn.farm_id));
+                }
 
-        println(format!("accounts: {:?}", accounts));
+                // restart only passes one reward, compared with its schedule before the instruction
+                if let Some(reward) = restart_or_add_txn.rewards.first() {
+                    let reward_key =
+                        keys::farm_reward_key(&restart_or_add_txn.farm_id, &reward.reward_vault);
+                    let previous_reward = farm_before(&farms, ordinal, &reward_key)
+                        .and_then(|reward_farm| reward_farm.rewards.into_iter().next());
+                    restart_or_add_txn.update_kind =
+                        reward_update_kind(previous_reward.as_ref(), farm_txn.block_time) as i32;
+                    restart_or_add_txn.previous_reward = previous_reward;
+                }
+            }
+            Some(Event::NewReward(new_reward_txn)) => {
+                if let Some(farm) = farms.get_at(ordinal, keys::farm_key(&new_reward_txn.farm_id)) {
+                    new_reward_txn.lp_mint = farm.lp_mint;
+                    new_reward_txn.creation_signature = farm.creation_signature;
+                } else {
+                    println(format!("farm not found: {}", new_reward_txn.farm_id));
+                }
+            }
+            _ => {}
+        }
+    }
+    if enriched_txns.transactions.is_empty() && enriched_txns.diagnostics.is_empty() {
+        return Ok(None);
+    }
 
-        let farm_program_index = accounts
-            .iter()
-            .position(|account| account.contains(FARM_PROGRAM_ID))
-            .ok_or("Farm program ID not found in accounts")?;
+    Ok(Some(enriched_txns))
+}
 
-        let create_instruction = compiled_instructions
-            .iter()
-            .find(|i| i.program_id_index == farm_program_index as u32)
-            .ok_or("Create instruction not found")?;
-
-        // Handle the case where the index doesn't exist
-        let index_of_lp_mint = create_instruction
-            .accounts
-            .get(6)
-            .ok_or("Index of LP mint not found")?;
-
-        let initialize_result =
-            process_initialize(&log_messages, &signature, &accounts, index_of_lp_mint);
-        if let Ok(Some(initialize_txn)) = initialize_result {
-            farm_transactions.transactions.push(RaydiumFarmTransaction {
-                event: Some(Event::Initialize(initialize_txn)),
+// the value of `key` before the changes made at `ordinal`
+fn farm_before(farms: &StoreGetProto<Farm>, ordinal: u64, key: &String) -> Option<Farm> {
+    if ordinal == 0 {
+        farms.get_first(key)
+    } else {
+        farms.get_at(ordinal - 1, key)
+    }
+}
+
+// A reward whose previous schedule had ended at the block time is restarted, whenever the new one
+// opens. A running reward is topped up or extended.
+fn reward_update_kind(
+    previous_reward: Option<&RewardSchedule>,
+    block_time: i64,
+) -> RewardUpdateKind {
+    match previous_reward {
+        None => RewardUpdateKind::Unknown,
+        Some(previous_reward) if previous_reward.end_time as i64 <= block_time => {
+            RewardUpdateKind::Restart
+        }
+        Some(_) => RewardUpdateKind::Extend,
+    }
+}
+
+#[substreams::handlers::store]
+fn store_farm_index(farm_txns: RaydiumEcoFarmTransactions, store: StoreAppend<String>) {
+    for farm_txn in successful_events(&farm_txns) {
+        // restart only reschedules rewards that were already created
+        let (farm_id, rewards) = match &farm_txn.event {
+            Some(Event::Initialize(initialize_txn)) => {
+                (&initialize_txn.farm_id, &initialize_txn.rewards)
+            }
+            Some(Event::NewReward(new_reward_txn)) => {
+                (&new_reward_txn.farm_id, &new_reward_txn.rewards)
+            }
+            _ => continue,
+        };
+
+        for reward in rewards {
+            let reward_key = keys::farm_reward_key(farm_id, &reward.reward_vault);
+            store.append(
+                farm_txn.ordinal as u64,
+                keys::farm_rewards_index_key(farm_id),
+                reward_key.clone(),
+            );
+            store.append(farm_txn.ordinal as u64, keys::FARM_INDEX_KEY, reward_key);
+        }
+    }
+}
+
+#[substreams::handlers::store]
+fn store_block_time(clock: Clock, store: StoreMaxInt64) {
+    store.max(0, keys::BLOCK_TIME_KEY, clock_timestamp(&clock));
+}
+
+// scans every farm reward on each block
+#[substreams::handlers::map]
+fn map_active_farms(
+    clock: Clock,
+    farm_index: StoreGetArray<String>,
+    farms: StoreGetProto<Farm>,
+    block_time_deltas: Deltas<DeltaInt64>,
+) -> Result<ActiveFarms, String> {
+    let block_time = clock_timestamp(&clock);
+    let previous_block_time = previous_block_time(&block_time_deltas, block_time);
+
+    // per farm, in index order: its rewards active now and whether one was active at the previous block
+    let mut farm_positions: HashMap<String, usize> = HashMap::new();
+    let mut farm_activity: Vec<(String, Vec<RewardSchedule>, bool)> = vec![];
+    for reward_key in farm_index
+        .get_last(keys::FARM_INDEX_KEY)
+        .unwrap_or_default()
+    {
+        let reward_farm = match farms.get_last(&reward_key) {
+            Some(reward_farm) => reward_farm,
+            None => continue,
+        };
+        let position = *farm_positions
+            .entry(reward_farm.farm_id.clone())
+            .or_insert_with(|| {
+                farm_activity.push((reward_farm.farm_id.clone(), vec![], false));
+                farm_activity.len() - 1
             });
+
+        let (_, active_rewards, was_active) = &mut farm_activity[position];
+        // the schedule at the previous block, before this block's restarts and added rewards
+        if let Some(previous_reward_farm) = farms.get_first(&reward_key) {
+            *was_active |= previous_reward_farm
+                .rewards
+                .iter()
+                .any(|reward| is_reward_active(reward, previous_block_time));
+        }
+        for reward in reward_farm.rewards {
+            if is_reward_active(&reward, block_time) {
+                active_rewards.push(reward);
+            }
         }
-        let restart_or_add_result = process_restart_or_add(&log_messages, &signature, &accounts);
-        if let Ok(Some(restart_or_add_txn)) = restart_or_add_result {
-            farm_transactions.transactions.push(RaydiumFarmTransaction {
-                event: Some(Event::RestartOrAdd(restart_or_add_txn)),
+    }
+
+    let mut active_farms = ActiveFarms {
+        slot: clock.number,
+        block_time,
+        ..Default::default()
+    };
+    for (farm_id, active_rewards, was_active) in farm_activity {
+        let is_active = !active_rewards.is_empty();
+        if is_active && !was_active {
+            active_farms.became_active.push(farm_id.clone());
+        }
+        if was_active && !is_active {
+            active_farms.became_inactive.push(farm_id.clone());
+        }
+        if is_active {
+            active_farms.farms.push(ActiveFarm {
+                lp_mint: farm_lp_mint(&farms, &farm_id),
+                farm_id,
+                rewards: active_rewards,
             });
         }
-        let new_reward_result = process_new_reward(&log_messages, &signature, &accounts);
-        if let Ok(Some(new_reward_txn)) = new_reward_result {
-            farm_transactions.transactions.push(RaydiumFarmTransaction {
-                event: Some(Event::NewReward(new_reward_txn)),
+    }
+
+    Ok(active_farms)
+}
+
+fn clock_timestamp(clock: &Clock) -> i64 {
+    clock
+        .timestamp
+        .as_ref()
+        .map(|timestamp| timestamp.seconds)
+        .unwrap_or_default()
+}
+
+#[substreams::handlers::store]
+fn store_schedule_buckets(farm_txns: RaydiumEcoFarmTransactions, store: StoreAppend<String>) {
+    for farm_txn in successful_events(&farm_txns) {
+        let (farm_id, rewards) = match &farm_txn.event {
+            Some(Event::Initialize(initialize_txn)) => {
+                (&initialize_txn.farm_id, &initialize_txn.rewards)
+            }
+            Some(Event::RestartOrAdd(restart_or_add_txn)) => {
+                (&restart_or_add_txn.farm_id, &restart_or_add_txn.rewards)
+            }
+            Some(Event::NewReward(new_reward_txn)) => {
+                (&new_reward_txn.farm_id, &new_reward_txn.rewards)
+            }
+            _ => continue,
+        };
+
+        for reward in rewards {
+            let reward_key = keys::farm_reward_key(farm_id, &reward.reward_vault);
+            store.append(
+                farm_txn.ordinal as u64,
+                keys::schedule_start_key(reward.start_time as i64 / keys::SCHEDULE_BUCKET_SECONDS),
+                reward_key.clone(),
+            );
+            store.append(
+                farm_txn.ordinal as u64,
+                keys::schedule_end_key(reward.end_time as i64 / keys::SCHEDULE_BUCKET_SECONDS),
+                reward_key,
+            );
+        }
+    }
+}
+
+// only reads the schedule buckets between the previous block time and this one
+#[substreams::handlers::map]
+fn map_farm_schedule_events(
+    clock: Clock,
+    schedule_buckets: StoreGetArray<String>,
+    farm_index: StoreGetArray<String>,
+    farms: StoreGetProto<Farm>,
+    block_time_deltas: Deltas<DeltaInt64>,
+) -> Result<Option<FarmScheduleEvents>, String> {
+    let block_time = clock_timestamp(&clock);
+    let previous_block_time = previous_block_time(&block_time_deltas, block_time);
+    if previous_block_time >= block_time {
+        return Ok(None);
+    }
+
+    let mut schedule_events = FarmScheduleEvents {
+        slot: clock.number,
+        block_time,
+        ..Default::default()
+    };
+    let started_rewards = crossed_rewards(
+        &schedule_buckets,
+        keys::schedule_start_key,
+        &farms,
+        previous_block_time,
+        block_time,
+        |reward| reward.start_time,
+    );
+    for (farm_id, rewards) in started_rewards {
+        // another reward of the farm was already paying out
+        if farm_active_before_block(&farm_index, &farms, &farm_id, previous_block_time) {
+            continue;
+        }
+        schedule_events.started.push(FarmStarted {
+            lp_mint: farm_lp_mint(&farms, &farm_id),
+            farm_id,
+            rewards,
+        });
+    }
+    let ended_rewards = crossed_rewards(
+        &schedule_buckets,
+        keys::schedule_end_key,
+        &farms,
+        previous_block_time,
+        block_time,
+        |reward| reward.end_time,
+    );
+    for (farm_id, rewards) in ended_rewards {
+        // the farm ends with its last reward
+        let (active, scheduled) = farm_reward_activity(&farm_index, &farms, &farm_id, block_time);
+        if active || scheduled {
+            continue;
+        }
+        schedule_events.ended.push(FarmEnded {
+            lp_mint: farm_lp_mint(&farms, &farm_id),
+            farm_id,
+            rewards,
+        });
+    }
+    if schedule_events.started.is_empty() && schedule_events.ended.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(schedule_events))
+}
+
+#[substreams::handlers::store]
+fn store_farm_states(
+    clock: Clock,
+    farm_txns: RaydiumEcoFarmTransactions,
+    schedule_events: FarmScheduleEvents,
+    farm_index: StoreGetArray<String>,
+    farms: StoreGetProto<Farm>,
+    store: StoreSetProto<FarmLifecycle>,
+) {
+    let block_time = clock_timestamp(&clock);
+    let set_state = |ordinal: u64, farm_id: &String, state: FarmState, signature: &String| {
+        store.set(
+            ordinal,
+            keys::farm_key(farm_id),
+            &FarmLifecycle {
+                farm_id: farm_id.clone(),
+                state: state as i32,
+                signature: signature.clone(),
+                slot: clock.number,
+                block_time,
+            },
+        );
+    };
+
+    let mut last_ordinal = 0;
+    for farm_txn in successful_events(&farm_txns) {
+        let ordinal = farm_txn.ordinal as u64;
+        last_ordinal = ordinal;
+
+        let (farm_id, signature, state) = match &farm_txn.event {
+            Some(Event::Initialize(initialize_txn)) => (
+                &initialize_txn.farm_id,
+                &initialize_txn.signature,
+                FarmState::Scheduled,
+            ),
+            Some(Event::RestartOrAdd(restart_or_add_txn)) => (
+                &restart_or_add_txn.farm_id,
+                &restart_or_add_txn.signature,
+                FarmState::Restarted,
+            ),
+            Some(Event::NewReward(new_reward_txn)) => (
+                &new_reward_txn.farm_id,
+                &new_reward_txn.signature,
+                FarmState::RewardAdded,
+            ),
+            Some(Event::CreatorWithdrawReward(withdraw_reward_txn)) => (
+                &withdraw_reward_txn.farm_id,
+                &withdraw_reward_txn.signature,
+                FarmState::Closed,
+            ),
+            _ => continue,
+        };
+
+        let (active, scheduled) = farm_reward_activity(&farm_index, &farms, farm_id, block_time);
+        if state == FarmState::Closed {
+            // withdrawing an ended reward of a farm with other rewards left does not close it
+            if !active && !scheduled {
+                set_state(ordinal, farm_id, state, signature);
+            }
+            continue;
+        }
+        set_state(ordinal, farm_id, state, signature);
+        // the schedule can start before the block it was set in
+        if active {
+            set_state(ordinal, farm_id, FarmState::Active, signature);
+        }
+    }
+
+    // the block time moves the farms after the block's transactions
+    let ordinal = last_ordinal + 1;
+    for started in schedule_events.started {
+        set_state(ordinal, &started.farm_id, FarmState::Active, &String::new());
+    }
+    for ended in schedule_events.ended {
+        set_state(ordinal, &ended.farm_id, FarmState::Ended, &String::new());
+    }
+}
+
+#[substreams::handlers::map]
+fn map_farm_state_transitions(
+    clock: Clock,
+    farm_state_deltas: Deltas<DeltaProto<FarmLifecycle>>,
+) -> Result<Option<FarmStateTransitions>, String> {
+    let mut state_transitions = FarmStateTransitions {
+        slot: clock.number,
+        block_time: clock_timestamp(&clock),
+        ..Default::default()
+    };
+
+    for delta in farm_state_deltas.deltas {
+        // a created key has the default, unknown, old value
+        let previous_state = delta.old_value.state();
+        let new_state = delta.new_value.state();
+        if previous_state == new_state {
+            continue;
+        }
+
+        state_transitions.transitions.push(FarmStateTransition {
+            farm_id: delta.new_value.farm_id,
+            previous_state: previous_state as i32,
+            new_state: new_state as i32,
+            signature: delta.new_value.signature,
+            invalid: !is_valid_transition(previous_state, new_state),
+        });
+    }
+    if state_transitions.transitions.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(state_transitions))
+}
+
+// transitions the farm program allows
+fn is_valid_transition(previous_state: FarmState, new_state: FarmState) -> bool {
+    matches!(
+        (previous_state, new_state),
+        (FarmState::Unknown, FarmState::Scheduled)
+            | (
+                FarmState::Scheduled,
+                FarmState::Active | FarmState::RewardAdded | FarmState::Ended
+            )
+            | (
+                FarmState::Active,
+                FarmState::Ended | FarmState::Restarted | FarmState::RewardAdded
+            )
+            | (
+                FarmState::Ended,
+                FarmState::Restarted | FarmState::RewardAdded | FarmState::Closed
+            )
+            | (
+                FarmState::Restarted | FarmState::RewardAdded,
+                FarmState::Active
+                    | FarmState::Ended
+                    | FarmState::Restarted
+                    | FarmState::RewardAdded
+            )
+            | (
+                FarmState::Closed,
+                FarmState::Restarted | FarmState::RewardAdded
+            )
+    )
+}
+
+// the store_farms keys of the farm's rewards
+fn farm_reward_keys(farm_index: &StoreGetArray<String>, farm_id: &String) -> Vec<String> {
+    farm_index
+        .get_last(keys::farm_rewards_index_key(farm_id))
+        .unwrap_or_default()
+}
+
+// whether a reward of the farm was active at `time` with the schedules from before the block's
+// restarts and added rewards
+fn farm_active_before_block(
+    farm_index: &StoreGetArray<String>,
+    farms: &StoreGetProto<Farm>,
+    farm_id: &String,
+    time: i64,
+) -> bool {
+    farm_reward_keys(farm_index, farm_id)
+        .iter()
+        .filter_map(|reward_key| farms.get_first(reward_key))
+        .flat_map(|reward_farm| reward_farm.rewards)
+        .any(|reward| is_reward_active(&reward, time))
+}
+
+// whether a reward of the farm is active at `time`, and whether one starts after it
+fn farm_reward_activity(
+    farm_index: &StoreGetArray<String>,
+    farms: &StoreGetProto<Farm>,
+    farm_id: &String,
+    time: i64,
+) -> (bool, bool) {
+    let rewards = farm_reward_keys(farm_index, farm_id)
+        .iter()
+        .filter_map(|reward_key| farms.get_last(reward_key))
+        .flat_map(|reward_farm| reward_farm.rewards)
+        .collect::<Vec<RewardSchedule>>();
+
+    (
+        rewards.iter().any(|reward| is_reward_active(reward, time)),
+        rewards.iter().any(|reward| reward.start_time as i64 > time),
+    )
+}
+
+#[substreams::handlers::store]
+fn store_farm_tvl(farm_txns: RaydiumEcoFarmTransactions, store: StoreAddBigInt) {
+    for farm_txn in successful_events(&farm_txns) {
+        match &farm_txn.event {
+            Some(Event::Deposit(deposit_txn)) => store.add(
+                farm_txn.ordinal as u64,
+                keys::farm_tvl_key(&deposit_txn.farm_id),
+                BigInt::from(deposit_txn.lp_amount),
+            ),
+            Some(Event::Withdraw(withdraw_txn)) => store.add(
+                farm_txn.ordinal as u64,
+                keys::farm_tvl_key(&withdraw_txn.farm_id),
+                BigInt::from(withdraw_txn.lp_amount).neg(),
+            ),
+            _ => {}
+        }
+    }
+}
+
+#[substreams::handlers::map]
+fn map_farm_tvl(
+    clock: Clock,
+    tvl_deltas: Deltas<DeltaBigInt>,
+    farms: StoreGetProto<Farm>,
+) -> Result<Option<FarmTvls>, String> {
+    // a farm has one delta per deposit or withdraw, from its TVL before the block to its TVL after
+    let mut farm_positions: HashMap<String, usize> = HashMap::new();
+    let mut farm_tvls: Vec<(String, BigInt, BigInt)> = vec![];
+    for delta in tvl_deltas.deltas {
+        let farm_id = match keys::farm_id_from_tvl_key(&delta.key) {
+            Some(farm_id) => farm_id.to_string(),
+            None => continue,
+        };
+        match farm_positions.get(&farm_id) {
+            Some(position) => farm_tvls[*position].2 = delta.new_value,
+            None => {
+                farm_positions.insert(farm_id.clone(), farm_tvls.len());
+                farm_tvls.push((farm_id, delta.old_value, delta.new_value));
+            }
+        }
+    }
+    if farm_tvls.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(FarmTvls {
+        slot: clock.number,
+        block_time: clock_timestamp(&clock),
+        farms: farm_tvls
+            .into_iter()
+            .map(|(farm_id, previous_tvl, tvl)| FarmTvl {
+                lp_mint: farm_lp_mint(&farms, &farm_id),
+                farm_id,
+                tvl_delta: (&tvl - &previous_tvl).to_string(),
+                tvl: tvl.to_string(),
+            })
+            .collect(),
+    }))
+}
+
+#[substreams::handlers::store]
+fn store_position_staked(farm_txns: RaydiumEcoFarmTransactions, store: StoreAddBigInt) {
+    for farm_txn in successful_events(&farm_txns) {
+        match &farm_txn.event {
+            Some(Event::Deposit(deposit_txn)) => store.add(
+                farm_txn.ordinal as u64,
+                keys::position_key(&deposit_txn.farm_id, &deposit_txn.user),
+                BigInt::from(deposit_txn.lp_amount),
+            ),
+            Some(Event::Withdraw(withdraw_txn)) => store.add(
+                farm_txn.ordinal as u64,
+                keys::position_key(&withdraw_txn.farm_id, &withdraw_txn.user),
+                BigInt::from(withdraw_txn.lp_amount).neg(),
+            ),
+            _ => {}
+        }
+    }
+}
+
+#[substreams::handlers::store]
+fn store_position_first_deposit(farm_txns: RaydiumEcoFarmTransactions, store: StoreMinInt64) {
+    for farm_txn in successful_events(&farm_txns) {
+        if let Some(Event::Deposit(deposit_txn)) = &farm_txn.event {
+            store.min(
+                farm_txn.ordinal as u64,
+                keys::position_key(&deposit_txn.farm_id, &deposit_txn.user),
+                farm_txn.slot as i64,
+            );
+        }
+    }
+}
+
+#[substreams::handlers::store]
+fn store_position_last_activity(farm_txns: RaydiumEcoFarmTransactions, store: StoreMaxInt64) {
+    for farm_txn in successful_events(&farm_txns) {
+        let (farm_id, user) = match &farm_txn.event {
+            Some(Event::Deposit(deposit_txn)) => (&deposit_txn.farm_id, &deposit_txn.user),
+            Some(Event::Withdraw(withdraw_txn)) => (&withdraw_txn.farm_id, &withdraw_txn.user),
+            Some(Event::Harvest(harvest_txn)) => (&harvest_txn.farm_id, &harvest_txn.user),
+            _ => continue,
+        };
+        store.max(
+            farm_txn.ordinal as u64,
+            keys::position_key(farm_id, user),
+            farm_txn.slot as i64,
+        );
+    }
+}
+
+// every position with activity in the block moves its last activity slot, and creates it on its
+// first activity
+#[substreams::handlers::map]
+fn map_position_changes(
+    clock: Clock,
+    last_activity_deltas: Deltas<DeltaInt64>,
+    staked: StoreGetBigInt,
+    first_deposits: StoreGetInt64,
+) -> Result<Option<PositionChanges>, String> {
+    let mut position_changes = PositionChanges {
+        slot: clock.number,
+        block_time: clock_timestamp(&clock),
+        ..Default::default()
+    };
+
+    let mut changed = HashSet::new();
+    for delta in last_activity_deltas.deltas {
+        if !changed.insert(delta.key.clone()) {
+            continue;
+        }
+        let (farm_id, user) = match keys::position_from_key(&delta.key) {
+            Some(position) => position,
+            None => continue,
+        };
+
+        let previous_staked = staked.get_first(&delta.key).unwrap_or_default();
+        let staked_amount = staked.get_last(&delta.key).unwrap_or_default();
+        position_changes.positions.push(PositionChange {
+            farm_id: farm_id.to_string(),
+            user: user.to_string(),
+            staked_delta: (&staked_amount - &previous_staked).to_string(),
+            staked: staked_amount.to_string(),
+            first_deposit_slot: first_deposits.get_last(&delta.key).unwrap_or_default() as u64,
+            last_activity_slot: delta.new_value as u64,
+            opened: delta.operation == Operation::Create,
+        });
+    }
+    if position_changes.positions.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(position_changes))
+}
+
+// farms, reward schedules, farm events with their rewards and positions for substreams-sink-sql,
+// see schema.sql
+#[substreams::handlers::map]
+fn db_out(
+    farm_txns: RaydiumEcoFarmTransactions,
+    farm_deltas: Deltas<DeltaProto<Farm>>,
+    position_changes: PositionChanges,
+) -> Result<DatabaseChanges, String> {
+    let mut tables = Tables::new();
+    db::farm_rows(&mut tables, &farm_deltas);
+    db::farm_event_rows(&mut tables, &farm_txns);
+    db::farm_event_reward_rows(&mut tables, &farm_txns);
+    db::position_rows(&mut tables, &position_changes);
+
+    Ok(tables.to_database_changes())
+}
+
+// every farm event flattened into the append-only table of schema.clickhouse.sql
+#[substreams::handlers::map]
+fn db_out_clickhouse(farm_txns: RaydiumEcoFarmTransactions) -> Result<DatabaseChanges, String> {
+    let mut tables = Tables::new();
+    db::flat_event_rows(&mut tables, &farm_txns);
+
+    Ok(tables.to_database_changes())
+}
+
+// farms, reward schedules, farm events with their rewards and positions for a subgraph, see
+// schema.graphql
+#[substreams::handlers::map]
+fn graph_out(
+    farm_txns: RaydiumEcoFarmTransactions,
+    farm_deltas: Deltas<DeltaProto<Farm>>,
+    position_changes: PositionChanges,
+) -> Result<EntityChanges, String> {
+    let mut tables = EntityTables::new();
+    graph::farm_entities(&mut tables, &farm_deltas);
+    graph::farm_event_entities(&mut tables, &farm_txns);
+    graph::position_entities(&mut tables, &position_changes);
+
+    Ok(tables.to_entity_changes())
+}
+
+// the rewards of the buckets from the previous block time to this one whose current schedule time
+// is in (previous_block_time, block_time], per farm in bucket order
+fn crossed_rewards(
+    schedule_buckets: &StoreGetArray<String>,
+    bucket_key: fn(i64) -> String,
+    farms: &StoreGetProto<Farm>,
+    previous_block_time: i64,
+    block_time: i64,
+    schedule_time: fn(&RewardSchedule) -> u32,
+) -> Vec<(String, Vec<RewardSchedule>)> {
+    let first_bucket = previous_block_time / keys::SCHEDULE_BUCKET_SECONDS;
+    let last_bucket = block_time / keys::SCHEDULE_BUCKET_SECONDS;
+
+    // a reward rescheduled within the same minute is listed more than once
+    let mut listed = HashSet::new();
+    let mut farm_positions: HashMap<String, usize> = HashMap::new();
+    let mut farm_rewards: Vec<(String, Vec<RewardSchedule>)> = vec![];
+    for reward_key in (first_bucket..=last_bucket).flat_map(|bucket| {
+        schedule_buckets
+            .get_last(bucket_key(bucket))
+            .unwrap_or_default()
+    }) {
+        if !listed.insert(reward_key.clone()) {
+            continue;
+        }
+        let reward_farm = match farms.get_last(&reward_key) {
+            Some(reward_farm) => reward_farm,
+            None => continue,
+        };
+        let reward = match reward_farm.rewards.into_iter().next() {
+            Some(reward) => reward,
+            None => continue,
+        };
+        let time = schedule_time(&reward) as i64;
+        if time <= previous_block_time || block_time < time {
+            continue;
+        }
+
+        let position = *farm_positions
+            .entry(reward_farm.farm_id.clone())
+            .or_insert_with(|| {
+                farm_rewards.push((reward_farm.farm_id.clone(), vec![]));
+                farm_rewards.len() - 1
             });
+        farm_rewards[position].1.push(reward);
+    }
+
+    farm_rewards
+}
+
+fn farm_lp_mint(farms: &StoreGetProto<Farm>, farm_id: &String) -> String {
+    farms
+        .get_last(keys::farm_key(farm_id))
+        .map(|farm| farm.lp_mint)
+        .unwrap_or_default()
+}
+
+// the first block has no previous block time
+fn previous_block_time(block_time_deltas: &Deltas<DeltaInt64>, block_time: i64) -> i64 {
+    block_time_deltas
+        .deltas
+        .iter()
+        .map(|delta| delta.old_value)
+        .find(|previous_block_time| *previous_block_time > 0)
+        .unwrap_or(block_time)
+}
+
+fn is_reward_active(reward: &RewardSchedule, time: i64) -> bool {
+    (reward.start_time as i64) <= time && time < reward.end_time as i64
+}
+
+// params are `key=value` pairs separated by `&`, e.g. "include_failed=true"
+fn include_failed_transactions(params: &str) -> Result<bool, String> {
+    let mut include_failed = false;
+    for param in params.split('&').filter(|param| !param.trim().is_empty()) {
+        match param.trim().split_once('=') {
+            Some(("include_failed", value)) => {
+                include_failed = value
+                    .parse::<bool>()
+                    .map_err(|_| format!("Invalid include_failed param: {}", value))?;
+            }
+            _ => return Err(format!("Unknown param: {}", param)),
         }
     }
-    if farm_transactions.transactions.len() == 0 {
-        return Ok(None); // Early return with None
+
+    Ok(include_failed)
+}
+
+fn transaction_signature(txn: &ConfirmedTransaction) -> Option<String> {
+    let signature = txn.transaction.as_ref()?.signatures.first()?;
+    Some(bs58::encode(signature).into_string())
+}
+
+fn references_program(txn: &ConfirmedTransaction, program: &Vec<u8>) -> bool {
+    let account_keys = txn
+        .transaction
+        .as_ref()
+        .and_then(|transaction| transaction.message.as_ref())
+        .map(|message| message.account_keys.iter());
+    let loaded_addresses = txn.meta.as_ref().map(|meta| {
+        meta.loaded_writable_addresses
+            .iter()
+            .chain(meta.loaded_readonly_addresses.iter())
+    });
+
+    account_keys
+        .into_iter()
+        .flatten()
+        .chain(loaded_addresses.into_iter().flatten())
+        .any(|account| account == program)
+}
+
+fn process_transaction(
+    txn: &ConfirmedTransaction,
+    include_failed: bool,
+) -> Result<Vec<RaydiumFarmTransaction>, String> {
+    let mut farm_transactions = vec![];
+
+    let meta = txn.meta.as_ref().ok_or("Transaction meta not found")?;
+
+    // a failed transaction can still have logged its farm instructions before erroring
+    let (status, error) = match &meta.err {
+        Some(err) => (
+            TransactionStatus::Failed,
+            transaction_error::decode_transaction_error(&err.err),
+        ),
+        None => (TransactionStatus::Success, String::new()),
+    };
+    if status == TransactionStatus::Failed && !include_failed {
+        return Ok(farm_transactions);
     }
 
-    Ok(Some(farm_transactions))
+    let transaction = txn.transaction.as_ref().ok_or("Transaction not found")?;
+    let message = transaction
+        .message
+        .as_ref()
+        .ok_or("Transaction message not found")?;
+
+    let log_messages = &meta.log_messages;
+
+    log_messages.iter().for_each(|log| {
+        println(format!("log: {:?}", log));
+    });
+
+    let signature = transaction_signature(txn).ok_or("Transaction signature not found")?;
+    println(format!("signature: {:?}", signature));
+
+    let compiled_instructions = &message.instructions;
+
+    let mut all_accounts = vec![];
+    all_accounts.extend(message.account_keys.iter());
+    all_accounts.extend(meta.loaded_writable_addresses.iter());
+    all_accounts.extend(meta.loaded_readonly_addresses.iter());
+
+    //get all accounts in base58
+    let accounts = all_accounts
+        .iter()
+        .map(|account| bs58::encode(account).into_string())
+        .collect::<Vec<String>>();
+
+    println(format!("accounts: {:?}", accounts));
+
+    // the fee payer is the transaction's first account, not necessarily the farm instruction's signer
+    let fee_payer = account_at(&accounts, 0)?;
+
+    let farm_program_index = accounts
+        .iter()
+        .position(|account| account.contains(FARM_PROGRAM_ID))
+        .ok_or("Farm program ID not found in accounts")?;
+
+    // farm instructions can be invoked directly or through another program (multisig, wrappers, ...)
+    let invocations = instructions::farm_invocations(
+        compiled_instructions,
+        &meta.inner_instructions,
+        farm_program_index as u32,
+    );
+    if invocations.is_empty() {
+        return Err("Farm instruction not found".to_string());
+    }
+
+    // the logs only carry reward vaults, their mints come from the token balances
+    let token_accounts = tokens::token_accounts(meta, &accounts);
+
+    // the n-th farm invocation logs the n-th slice, truncated logs leave the last ones empty
+    let log_slices = farm_log_slices(log_messages);
+
+    for (position, invocation) in invocations.iter().enumerate() {
+        let invocation_logs = log_slices.get(position).copied().unwrap_or(&[]);
+        let event = process_farm_instruction(
+            invocation,
+            invocation_logs,
+            &signature,
+            &accounts,
+            &token_accounts,
+        )?;
+        if let Some(event) = event {
+            farm_transactions.push(RaydiumFarmTransaction {
+                event: Some(event),
+                instruction_index: invocation.instruction_index,
+                inner_instruction_index: invocation.inner_instruction_index,
+                stack_height: invocation.stack_height,
+                status: status as i32,
+                error: error.clone(),
+                fee_payer: fee_payer.clone(),
+                id: event_id(
+                    &signature,
+                    invocation.instruction_index,
+                    invocation.inner_instruction_index,
+                ),
+                // block context is set by the caller
+                ..Default::default()
+            });
+        }
+    }
+
+    Ok(farm_transactions)
+}
+
+// a transaction can invoke the farm program several times, the signature alone is not unique
+fn event_id(
+    signature: &String,
+    instruction_index: u32,
+    inner_instruction_index: Option<u32>,
+) -> String {
+    match inner_instruction_index {
+        Some(inner_instruction_index) => format!(
+            "{}:{}:{}",
+            signature, instruction_index, inner_instruction_index
+        ),
+        None => format!("{}:{}", signature, instruction_index),
+    }
+}
+
+fn process_farm_instruction(
+    invocation: &FarmInvocation,
+    log_messages: &[String],
+    signature: &String,
+    accounts: &[String],
+    token_accounts: &HashMap<String, TokenAccount>,
+) -> Result<Option<Event>, String> {
+    let decoded_instruction = instructions::decode(invocation.data);
+    println(format!("decoded instruction: {:?}", decoded_instruction));
+
+    match decoded_instruction {
+        Ok(FarmInstruction::Create { rewards, .. }) => {
+            let farm_id = invocation.account(accounts, instructions::CREATE_FARM_FARM_ACCOUNT)?;
+            let user = invocation.account(accounts, instructions::CREATE_FARM_OWNER_ACCOUNT)?;
+            let (lp_mint, lp_mint_source) = resolve_lp_mint(invocation, accounts, token_accounts)?;
+
+            let mut initialize_txn = initialize_from_instruction(
+                invocation,
+                &rewards,
+                signature,
+                accounts,
+                &farm_id,
+                &user,
+                (lp_mint.clone(), lp_mint_source),
+            )?;
+
+            // the instruction data is authoritative, the logs cross-check it and add the on-chain clock
+            let initialize_result = process_initialize(
+                log_messages,
+                signature,
+                &farm_id,
+                &user,
+                &lp_mint,
+                lp_mint_source,
+            );
+            if let Ok(Some(logged_txn)) = initialize_result {
+                check_logged_schedules(
+                    "initialize",
+                    &mut initialize_txn.rewards,
+                    &logged_txn.rewards,
+                );
+            }
+            resolve_reward_mints(&mut initialize_txn.rewards, token_accounts);
+            Ok(Some(Event::Initialize(initialize_txn)))
+        }
+        Ok(FarmInstruction::RestartReward {
+            open_time,
+            end_time,
+            reward_per_second,
+        }) => {
+            let farm_id =
+                invocation.account(accounts, instructions::RESTART_REWARD_FARM_ACCOUNT)?;
+            let user = invocation.account(accounts, instructions::RESTART_REWARD_OWNER_ACCOUNT)?;
+            let mut restart_or_add_txn = RestartOrAddTransaction {
+                signature: signature.to_string(),
+                farm_id: farm_id.clone(),
+                user: user.clone(),
+                start_time: open_time as u32,
+                end_time: end_time as u32,
+                rewards: vec![RewardSchedule {
+                    reward_index: None,
+                    reward_per_second,
+                    start_time: open_time as u32,
+                    end_time: end_time as u32,
+                    current_time: 0,
+                    reward_vault: invocation
+                        .account(accounts, instructions::RESTART_REWARD_VAULT_ACCOUNT)?,
+                    // only the vault is passed to restart, the mint is resolved from the token balances
+                    reward_mint: String::new(),
+                    reward_decimals: 0,
+                }],
+                // not known from the instruction, see `map_enriched_farm_txns`
+                lp_mint: String::new(),
+                creation_signature: String::new(),
+                update_kind: RewardUpdateKind::Unknown as i32,
+                previous_reward: None,
+            };
+
+            let restart_or_add_result =
+                process_restart_or_add(log_messages, signature, &farm_id, &user);
+            if let Ok(Some(logged_txn)) = restart_or_add_result {
+                check_logged_schedules(
+                    "restart",
+                    &mut restart_or_add_txn.rewards,
+                    &logged_txn.rewards,
+                );
+            }
+            resolve_reward_mints(&mut restart_or_add_txn.rewards, token_accounts);
+            Ok(Some(Event::RestartOrAdd(restart_or_add_txn)))
+        }
+        Ok(FarmInstruction::AddReward(reward)) => {
+            let farm_id = invocation.account(accounts, instructions::ADD_REWARD_FARM_ACCOUNT)?;
+            let user = invocation.account(accounts, instructions::ADD_REWARD_OWNER_ACCOUNT)?;
+            let mut new_reward_txn = NewRewardTransaction {
+                signature: signature.to_string(),
+                farm_id: farm_id.clone(),
+                user: user.clone(),
+                start_time: reward.open_time as u32,
+                end_time: reward.end_time as u32,
+                rewards: vec![RewardSchedule {
+                    reward_index: None,
+                    reward_per_second: reward.reward_per_second,
+                    start_time: reward.open_time as u32,
+                    end_time: reward.end_time as u32,
+                    current_time: 0,
+                    reward_vault: invocation
+                        .account(accounts, instructions::ADD_REWARD_VAULT_ACCOUNT)?,
+                    reward_mint: invocation
+                        .account(accounts, instructions::ADD_REWARD_MINT_ACCOUNT)?,
+                    reward_decimals: 0,
+                }],
+                // not known from the instruction, see `map_enriched_farm_txns`
+                lp_mint: String::new(),
+                creation_signature: String::new(),
+            };
+
+            let new_reward_result = process_new_reward(log_messages, signature, &farm_id, &user);
+            if let Ok(Some(logged_txn)) = new_reward_result {
+                check_logged_schedules(
+                    "add reward",
+                    &mut new_reward_txn.rewards,
+                    &logged_txn.rewards,
+                );
+            }
+            resolve_reward_mints(&mut new_reward_txn.rewards, token_accounts);
+            Ok(Some(Event::NewReward(new_reward_txn)))
+        }
+        Ok(FarmInstruction::Deposit { amount }) => {
+            let deposit_txn =
+                deposit_from_instruction(invocation, amount, signature, accounts, token_accounts)?;
+            // a zero amount deposit is how rewards are harvested
+            if amount == 0 {
+                return Ok(Some(Event::Harvest(HarvestTransaction {
+                    signature: deposit_txn.signature,
+                    farm_id: deposit_txn.farm_id,
+                    user: deposit_txn.user,
+                    user_ledger: deposit_txn.user_ledger,
+                    rewards: deposit_txn.rewards,
+                })));
+            }
+            Ok(Some(Event::Deposit(deposit_txn)))
+        }
+        Ok(FarmInstruction::Withdraw { amount }) => {
+            let withdraw_txn =
+                withdraw_from_instruction(invocation, amount, signature, accounts, token_accounts)?;
+            if amount == 0 {
+                return Ok(Some(Event::Harvest(HarvestTransaction {
+                    signature: withdraw_txn.signature,
+                    farm_id: withdraw_txn.farm_id,
+                    user: withdraw_txn.user,
+                    user_ledger: withdraw_txn.user_ledger,
+                    rewards: withdraw_txn.rewards,
+                })));
+            }
+            Ok(Some(Event::Withdraw(withdraw_txn)))
+        }
+        Ok(FarmInstruction::CreatorWithdrawReward) => {
+            let transfers = tokens::token_transfers(&invocation.inner_instructions, accounts);
+            // a single (reward vault, creator reward token account) pair
+            let reward = reward_payouts(
+                invocation,
+                accounts,
+                token_accounts,
+                &transfers,
+                instructions::CREATOR_WITHDRAW_REWARD_VAULT_ACCOUNT,
+            )
+            .into_iter()
+            .next();
+
+            Ok(Some(Event::CreatorWithdrawReward(
+                CreatorWithdrawRewardTransaction {
+                    signature: signature.to_string(),
+                    farm_id: invocation
+                        .account(accounts, instructions::CREATOR_WITHDRAW_REWARD_FARM_ACCOUNT)?,
+                    user: invocation.account(
+                        accounts,
+                        instructions::CREATOR_WITHDRAW_REWARD_OWNER_ACCOUNT,
+                    )?,
+                    reward,
+                },
+            )))
+        }
+        Ok(FarmInstruction::Unknown(tag)) => {
+            println(format!("untracked farm instruction: {}", tag));
+            Ok(None)
+        }
+        Err(err) => {
+            // instruction data could not be decoded, fall back to the program logs
+            println(format!("failed to decode farm instruction: {}", err));
+
+            if let (Ok(farm_id), Ok(user), Ok((lp_mint, lp_mint_source))) = (
+                invocation.account(accounts, instructions::CREATE_FARM_FARM_ACCOUNT),
+                invocation.account(accounts, instructions::CREATE_FARM_OWNER_ACCOUNT),
+                resolve_lp_mint(invocation, accounts, token_accounts),
+            ) {
+                let initialize_result = process_initialize(
+                    log_messages,
+                    signature,
+                    &farm_id,
+                    &user,
+                    &lp_mint,
+                    lp_mint_source,
+                );
+                if let Ok(Some(mut initialize_txn)) = initialize_result {
+                    resolve_reward_mints(&mut initialize_txn.rewards, token_accounts);
+                    return Ok(Some(Event::Initialize(initialize_txn)));
+                }
+            }
+            if let (Ok(farm_id), Ok(user)) = (
+                invocation.account(accounts, instructions::RESTART_REWARD_FARM_ACCOUNT),
+                invocation.account(accounts, instructions::RESTART_REWARD_OWNER_ACCOUNT),
+            ) {
+                let restart_or_add_result =
+                    process_restart_or_add(log_messages, signature, &farm_id, &user);
+                if let Ok(Some(mut restart_or_add_txn)) = restart_or_add_result {
+                    resolve_reward_mints(&mut restart_or_add_txn.rewards, token_accounts);
+                    return Ok(Some(Event::RestartOrAdd(restart_or_add_txn)));
+                }
+            }
+            if let (Ok(farm_id), Ok(user)) = (
+                invocation.account(accounts, instructions::ADD_REWARD_FARM_ACCOUNT),
+                invocation.account(accounts, instructions::ADD_REWARD_OWNER_ACCOUNT),
+            ) {
+                let new_reward_result =
+                    process_new_reward(log_messages, signature, &farm_id, &user);
+                if let Ok(Some(mut new_reward_txn)) = new_reward_result {
+                    resolve_reward_mints(&mut new_reward_txn.rewards, token_accounts);
+                    return Ok(Some(Event::NewReward(new_reward_txn)));
+                }
+            }
+            Ok(None)
+        }
+    }
+}
+
+// Splits the transaction logs into one slice per farm program invocation, in execution order.
+// Each slice runs from the `invoke` line up to the matching `success` / `failed` line, so it
+// also contains the logs of the programs the farm program called into.
+pub fn farm_log_slices(log_messages: &[String]) -> Vec<&[String]> {
+    let invoke_prefix = format!("Program {} invoke", FARM_PROGRAM_ID);
+    let success = format!("Program {} success", FARM_PROGRAM_ID);
+    let failed = format!("Program {} failed", FARM_PROGRAM_ID);
+
+    let mut slices = vec![];
+    let mut start: Option<usize> = None;
+    for (index, log) in log_messages.iter().enumerate() {
+        if log.starts_with(&invoke_prefix) {
+            start = Some(index);
+        } else if log.starts_with(&success) || log.starts_with(&failed) {
+            if let Some(start_index) = start.take() {
+                slices.push(&log_messages[start_index..=index]);
+            }
+        }
+    }
+    // logs were truncated in the middle of the last invocation
+    if let Some(start_index) = start {
+        slices.push(&log_messages[start_index..]);
+    }
+
+    slices
+}
+
+fn account_at(accounts: &[String], index: usize) -> Result<String, String> {
+    accounts
+        .get(index)
+        .cloned()
+        .ok_or(format!("Account at index {} not found", index))
+}
+
+// The decoded schedules are kept, a logged schedule that disagrees is only reported. The on-chain
+// clock (`current_time`) is not in the instruction data and is the one value taken from the logs.
+fn check_logged_schedules(
+    instruction: &str,
+    schedules: &mut [RewardSchedule],
+    logged_schedules: &[RewardSchedule],
+) {
+    for (schedule, logged_schedule) in schedules.iter_mut().zip(logged_schedules.iter()) {
+        if (
+            schedule.reward_per_second,
+            schedule.start_time,
+            schedule.end_time,
+        ) != (
+            logged_schedule.reward_per_second,
+            logged_schedule.start_time,
+            logged_schedule.end_time,
+        ) {
+            println(format!(
+                "{} logs and instruction data disagree: {:?} vs {:?}",
+                instruction, logged_schedule, schedule
+            ));
+        }
+        schedule.current_time = logged_schedule.current_time;
+    }
+}
+
+// The vault's token balances are authoritative for the reward mint, the mint account passed to the
+// instruction (Init and add reward only) is kept when the vault has no balance in the transaction
+fn resolve_reward_mints(
+    schedules: &mut [RewardSchedule],
+    token_accounts: &HashMap<String, TokenAccount>,
+) {
+    for schedule in schedules.iter_mut() {
+        match token_accounts.get(&schedule.reward_vault) {
+            Some(token_account) => {
+                if !schedule.reward_mint.is_empty() && schedule.reward_mint != token_account.mint {
+                    println(format!(
+                        "reward mint {} does not match vault {} mint {}",
+                        schedule.reward_mint, schedule.reward_vault, token_account.mint
+                    ));
+                }
+                schedule.reward_mint = token_account.mint.clone();
+                schedule.reward_decimals = token_account.decimals;
+            }
+            None => println(format!(
+                "no token balance found for reward vault {}",
+                schedule.reward_vault
+            )),
+        }
+    }
+}
+
+// earliest start and latest end over the rewards, 0 when there are none
+fn schedule_window(rewards: &[RewardSchedule]) -> (u32, u32) {
+    (
+        rewards
+            .iter()
+            .map(|reward| reward.start_time)
+            .min()
+            .unwrap_or(0),
+        rewards
+            .iter()
+            .map(|reward| reward.end_time)
+            .max()
+            .unwrap_or(0),
+    )
+}
+
+fn log_field<'a>(log: &'a str, name: &str) -> Option<&'a str> {
+    log.split(name)
+        .nth(1)?
+        .split(',')
+        .next()
+        .map(|field| field.trim())
+}
+
+// "process_initialize reward_per_second 1653, begin:1737491275, current:1737490622, end:1738096075"
+fn parse_initialize_schedule(reward_index: usize, log: &str) -> Option<RewardSchedule> {
+    Some(RewardSchedule {
+        reward_index: Some(reward_index as u32),
+        reward_per_second: log_field(log, "reward_per_second ")?.parse().ok()?,
+        start_time: log_field(log, "begin:")?.parse().ok()?,
+        end_time: log_field(log, "end:")?.parse().ok()?,
+        current_time: log_field(log, "current:")?.parse().ok()?,
+        reward_vault: String::new(),
+        reward_mint: String::new(),
+        reward_decimals: 0,
+    })
+}
+
+// "process_creator_restart: EVfHjrgu9KFV4889AdyBNtB7jgBhAaPZeSAJ9sY163vD, 1740777211, 1741382011, 16"
+// "process_admin_add_reward_token: 6npFrUXvt7yniYerAwcBjg5SKspxN4tZbGFxEqMFEZHJ, 1740785220, 1741390020, 1, 0"
+fn parse_reward_update_schedule(prefix: &str, log: &str) -> Option<RewardSchedule> {
+    let mut fields = log
+        .split(prefix)
+        .nth(1)?
+        .split(", ")
+        .map(|field| field.trim());
+
+    Some(RewardSchedule {
+        reward_index: None,
+        reward_vault: fields.next()?.to_string(),
+        start_time: fields.next()?.parse().ok()?,
+        end_time: fields.next()?.parse().ok()?,
+        reward_per_second: fields.next()?.parse().ok()?,
+        current_time: 0,
+        reward_mint: String::new(),
+        reward_decimals: 0,
+    })
+}
+
+// Init passes the LP vault right before the LP mint, the vault's token balance confirms the mint
+// when the transaction carries it and takes over when the instruction account disagrees
+fn resolve_lp_mint(
+    invocation: &FarmInvocation,
+    accounts: &[String],
+    token_accounts: &HashMap<String, TokenAccount>,
+) -> Result<(String, LpMintSource), String> {
+    let instruction_lp_mint = invocation
+        .account(accounts, instructions::CREATE_FARM_LP_MINT_ACCOUNT)
+        .ok();
+    let lp_vault_mint = invocation
+        .account(accounts, instructions::CREATE_FARM_LP_VAULT_ACCOUNT)
+        .ok()
+        .and_then(|lp_vault| token_accounts.get(&lp_vault))
+        .map(|token_account| token_account.mint.clone());
+
+    match (instruction_lp_mint, lp_vault_mint) {
+        (Some(lp_mint), Some(lp_vault_mint)) if lp_mint == lp_vault_mint => {
+            Ok((lp_mint, LpMintSource::Verified))
+        }
+        (Some(lp_mint), None) => Ok((lp_mint, LpMintSource::Instruction)),
+        (instruction_lp_mint, Some(lp_vault_mint)) => {
+            println(format!(
+                "LP mint account {:?} does not match LP vault mint {}",
+                instruction_lp_mint, lp_vault_mint
+            ));
+            Ok((lp_vault_mint, LpMintSource::TokenBalance))
+        }
+        (None, None) => Err("LP mint not found".to_string()),
+    }
+}
+
+pub fn initialize_from_instruction(
+    invocation: &FarmInvocation,
+    rewards: &[RewardTimeInfo],
+    signature: &String,
+    accounts: &[String],
+    farm_id: &String,
+    user: &String,
+    (lp_mint, lp_mint_source): (String, LpMintSource),
+) -> Result<InitializeTransaction, String> {
+    let reward_schedules = rewards
+        .iter()
+        .enumerate()
+        .filter(|(_, reward)| reward.is_set != 0)
+        .map(|(reward_index, reward)| {
+            Ok(RewardSchedule {
+                reward_index: Some(reward_index as u32),
+                reward_per_second: reward.reward_per_second,
+                start_time: reward.open_time as u32,
+                end_time: reward.end_time as u32,
+                current_time: 0,
+                reward_vault: invocation.account(
+                    accounts,
+                    instructions::create_farm_reward_vault_account(reward_index),
+                )?,
+                reward_mint: invocation.account(
+                    accounts,
+                    instructions::create_farm_reward_mint_account(reward_index),
+                )?,
+                reward_decimals: 0,
+            })
+        })
+        .collect::<Result<Vec<RewardSchedule>, String>>()?;
+    // earliest start and latest end over all the rewards set at creation
+    let (start_time, end_time) = schedule_window(&reward_schedules);
+
+    Ok(InitializeTransaction {
+        signature: signature.to_string(),
+        farm_id: farm_id.to_string(),
+        user: user.to_string(),
+        lp_mint,
+        start_time,
+        end_time,
+        rewards: reward_schedules,
+        lp_mint_source: lp_mint_source as i32,
+    })
+}
+
+pub fn deposit_from_instruction(
+    invocation: &FarmInvocation,
+    amount: u64,
+    signature: &String,
+    accounts: &[String],
+    token_accounts: &HashMap<String, TokenAccount>,
+) -> Result<DepositTransaction, String> {
+    let lp_vault = invocation.account(accounts, instructions::DEPOSIT_LP_VAULT_ACCOUNT)?;
+    let transfers = tokens::token_transfers(&invocation.inner_instructions, accounts);
+
+    let transferred = if transfers.is_empty() {
+        token_accounts
+            .get(&lp_vault)
+            .map(|lp_vault_account| lp_vault_account.received())
+            .unwrap_or_default()
+    } else {
+        transferred_amount(&transfers, None, Some(&lp_vault))
+    };
+    if transferred != amount {
+        println(format!(
+            "deposit amount {} does not match LP transferred {}",
+            amount, transferred
+        ));
+    }
+
+    Ok(DepositTransaction {
+        signature: signature.to_string(),
+        farm_id: invocation.account(accounts, instructions::DEPOSIT_FARM_ACCOUNT)?,
+        user: invocation.account(accounts, instructions::DEPOSIT_OWNER_ACCOUNT)?,
+        user_ledger: invocation.account(accounts, instructions::DEPOSIT_LEDGER_ACCOUNT)?,
+        user_lp_token_account: invocation
+            .account(accounts, instructions::DEPOSIT_USER_LP_ACCOUNT)?,
+        lp_mint: token_accounts
+            .get(&lp_vault)
+            .map(|lp_vault_account| lp_vault_account.mint.clone())
+            .unwrap_or_default(),
+        lp_amount: amount,
+        rewards: reward_payouts(
+            invocation,
+            accounts,
+            token_accounts,
+            &transfers,
+            instructions::DEPOSIT_REWARD_ACCOUNTS_START,
+        ),
+    })
+}
+
+pub fn withdraw_from_instruction(
+    invocation: &FarmInvocation,
+    amount: u64,
+    signature: &String,
+    accounts: &[String],
+    token_accounts: &HashMap<String, TokenAccount>,
+) -> Result<WithdrawTransaction, String> {
+    let lp_vault = invocation.account(accounts, instructions::WITHDRAW_LP_VAULT_ACCOUNT)?;
+    let transfers = tokens::token_transfers(&invocation.inner_instructions, accounts);
+
+    let transferred = if transfers.is_empty() {
+        token_accounts
+            .get(&lp_vault)
+            .map(|lp_vault_account| lp_vault_account.sent())
+            .unwrap_or_default()
+    } else {
+        transferred_amount(&transfers, Some(&lp_vault), None)
+    };
+    if transferred != amount {
+        println(format!(
+            "withdraw amount {} does not match LP transferred {}",
+            amount, transferred
+        ));
+    }
+
+    Ok(WithdrawTransaction {
+        signature: signature.to_string(),
+        farm_id: invocation.account(accounts, instructions::WITHDRAW_FARM_ACCOUNT)?,
+        user: invocation.account(accounts, instructions::WITHDRAW_OWNER_ACCOUNT)?,
+        user_ledger: invocation.account(accounts, instructions::WITHDRAW_LEDGER_ACCOUNT)?,
+        user_lp_token_account: invocation
+            .account(accounts, instructions::WITHDRAW_USER_LP_ACCOUNT)?,
+        lp_mint: token_accounts
+            .get(&lp_vault)
+            .map(|lp_vault_account| lp_vault_account.mint.clone())
+            .unwrap_or_default(),
+        lp_amount: amount,
+        rewards: reward_payouts(
+            invocation,
+            accounts,
+            token_accounts,
+            &transfers,
+            instructions::WITHDRAW_REWARD_ACCOUNTS_START,
+        ),
+    })
+}
+
+// Total of the transfers matching `source` and / or `destination`
+fn transferred_amount(
+    transfers: &[TokenTransfer],
+    source: Option<&String>,
+    destination: Option<&String>,
+) -> u64 {
+    transfers
+        .iter()
+        .filter(|transfer| source.is_none_or(|source| &transfer.source == source))
+        .filter(|transfer| {
+            destination.is_none_or(|destination| &transfer.destination == destination)
+        })
+        .map(|transfer| transfer.amount)
+        .sum()
+}
+
+// Deposits and withdraws harvest the pending rewards: every (reward vault, user reward token account)
+// pair after `first_reward_account` is paid out what the farm transferred from the vault to the user.
+// When the farm's CPIs can't be told apart (no stack heights) the balance changes are used instead.
+fn reward_payouts(
+    invocation: &FarmInvocation,
+    accounts: &[String],
+    token_accounts: &HashMap<String, TokenAccount>,
+    transfers: &[TokenTransfer],
+    first_reward_account: usize,
+) -> Vec<RewardPayout> {
+    let mut payouts = vec![];
+
+    let mut position = first_reward_account;
+    while let (Ok(reward_vault), Ok(user_token_account)) = (
+        invocation.account(accounts, position),
+        invocation.account(accounts, position + 1),
+    ) {
+        let vault_account = token_accounts
+            .get(&reward_vault)
+            .cloned()
+            .unwrap_or_default();
+        let received = token_accounts
+            .get(&user_token_account)
+            .map(|user_account| user_account.received())
+            .unwrap_or_default();
+
+        let amount = if transfers.is_empty() {
+            vault_account.sent().min(received)
+        } else {
+            transferred_amount(transfers, Some(&reward_vault), Some(&user_token_account))
+        };
+        if amount != received {
+            println(format!(
+                "reward payout {} from {} does not match {} balance change {}",
+                amount, reward_vault, user_token_account, received
+            ));
+        }
+
+        payouts.push(RewardPayout {
+            amount,
+            reward_vault,
+            reward_mint: vault_account.mint,
+            reward_decimals: vault_account.decimals,
+            user_token_account,
+        });
+        position += 2;
+    }
+
+    payouts
 }
 
 pub fn process_initialize(
-    log_messages: &Vec<String>,
+    log_messages: &[String],
     signature: &String,
-    accounts: &Vec<String>,
-    index_of_lp_mint: &u8,
+    farm_id: &String,
+    user: &String,
+    lp_mint: &String,
+    lp_mint_source: LpMintSource,
 ) -> Result<Option<InitializeTransaction>, String> {
     //check if farm program id is in the logs
     let init_farm = log_messages.iter().any(|log| log.contains(FARM_PROGRAM_ID));
@@ -111,66 +1685,45 @@ pub fn process_initialize(
         return Ok(None); // Early return with None
     }
 
-    let lp_mint = accounts
-        .get(*index_of_lp_mint as usize)
-        .ok_or("LP mint account not found")?;
-
     println(format!(
         "process_initialize_logs: {:?}",
         process_initialize_logs
     ));
-    let user = accounts.get(0);
-    let farm_id = accounts.get(1);
 
     println(format!(
         "user: {:?}, farm_id: {:?}, lp_mint: {:?}",
         user, farm_id, lp_mint
     ));
 
-    // Finding the earliest start time
-    let mut start_time: u32 = 0;
-    for message in process_initialize_logs {
-        if let Some(split) = message.split("begin:").nth(1) {
-            if let Some(temp_start_time) =
-                split.split(",").next().and_then(|s| s.parse::<u32>().ok())
-            {
-                start_time = if start_time == 0 {
-                    temp_start_time
-                } else {
-                    start_time.min(temp_start_time)
-                };
-            }
-        }
-    }
     // "Instruction: Init", "process_initialize accounts len:17", "process_initialize reward_per_second 1653, begin:1737491275, current:1737490622, end:1738096075", "process_initialize reward_per_second 3, begin:1737491287, current:1737490622, end:1738096087"
-    println(format!("start_time: {:?}", start_time));
-
-    // Finding the latest end time
-    let mut end_time: u32 = 0;
-    for message in process_initialize_logs {
-        if let Some(split) = message.split("end:").nth(1) {
-            if let Some(temp_end_time) = split.split(",").next().and_then(|s| s.parse::<u32>().ok())
-            {
-                end_time = end_time.max(temp_end_time);
-            }
-        }
-    }
-    println(format!("end_time: {:?}", end_time));
+    let rewards = process_initialize_logs
+        .iter()
+        .enumerate()
+        .filter_map(|(reward_index, message)| parse_initialize_schedule(reward_index, message))
+        .collect::<Vec<RewardSchedule>>();
+    let (start_time, end_time) = schedule_window(&rewards);
+    println(format!(
+        "start_time: {:?}, end_time: {:?}",
+        start_time, end_time
+    ));
 
     Ok(Some(InitializeTransaction {
         signature: signature.to_string(),
-        farm_id: farm_id.unwrap().to_string(),
-        user: user.unwrap().to_string(),
+        farm_id: farm_id.to_string(),
+        user: user.to_string(),
         lp_mint: lp_mint.to_string(),
         start_time,
         end_time,
+        rewards,
+        lp_mint_source: lp_mint_source as i32,
     }))
 }
 
 pub fn process_restart_or_add(
-    log_messages: &Vec<String>,
+    log_messages: &[String],
     signature: &String,
-    accounts: &Vec<String>,
+    farm_id: &String,
+    user: &String,
 ) -> Result<Option<RestartOrAddTransaction>, String> {
     let restart_or_add_farm = log_messages.iter().any(|log| log.contains(FARM_PROGRAM_ID));
     if !restart_or_add_farm {
@@ -184,61 +1737,38 @@ pub fn process_restart_or_add(
         return Ok(None); // Early return with None
     }
 
-    let user = accounts.get(0);
-    let farm_id = accounts.get(1);
-    // could get rewards tokens from messages, but are only given the token account not the mint address
-    //lp mint token account in accounts, but we need to mint address :-'(
-    //no lp mint in the logs
-
-    // println(format!("user: {:?}, farm_id: {:?}", user, farm_id));
-    // println(format!("accounts: {:?}", accounts));
+    // the logs only give the reward vault token account, its mint is resolved from the token balances
 
     //["process_creator_restart: EVfHjrgu9KFV4889AdyBNtB7jgBhAaPZeSAJ9sY163vD,
     // 1740777211,
     // 1741382011, 16", "process_creator_restart: DpiGX6UpwH7pz9YKka2t6zyWFfBQyiq4ihCy7nzGciEh, 1740777232, 1741382
     // 032, 3"]
 
-    // // Finding the earliest start time
-    let mut start_time: u32 = 0;
-    for message in reward_messages {
-        if let Some(split) = message.split("process_creator_restart: ").nth(1) {
-            if let Some(temp_start_time) =
-                split.split(", ").nth(1).and_then(|s| s.parse::<u32>().ok())
-            {
-                start_time = if start_time == 0 {
-                    temp_start_time
-                } else {
-                    start_time.min(temp_start_time)
-                };
-            }
-        }
-    }
-
-    // // Finding the latest end time
-    let mut end_time: u32 = 0;
-    for message in reward_messages {
-        if let Some(split) = message.split("process_creator_restart: ").nth(1) {
-            if let Some(temp_end_time) =
-                split.split(", ").nth(2).and_then(|s| s.parse::<u32>().ok())
-            {
-                end_time = end_time.max(temp_end_time);
-            }
-        }
-    }
+    let rewards = reward_messages
+        .iter()
+        .filter_map(|message| parse_reward_update_schedule("process_creator_restart: ", message))
+        .collect::<Vec<RewardSchedule>>();
+    let (start_time, end_time) = schedule_window(&rewards);
 
     Ok(Some(RestartOrAddTransaction {
         signature: signature.to_string(),
-        farm_id: farm_id.unwrap().to_string(),
-        user: user.unwrap().to_string(),
+        farm_id: farm_id.to_string(),
+        user: user.to_string(),
         start_time,
         end_time,
+        rewards,
+        lp_mint: String::new(),
+        creation_signature: String::new(),
+        update_kind: RewardUpdateKind::Unknown as i32,
+        previous_reward: None,
     }))
 }
 
 pub fn process_new_reward(
-    log_messages: &Vec<String>,
+    log_messages: &[String],
     signature: &String,
-    accounts: &Vec<String>,
+    farm_id: &String,
+    user: &String,
 ) -> Result<Option<NewRewardTransaction>, String> {
     let new_reward_farm = log_messages.iter().any(|log| log.contains(FARM_PROGRAM_ID));
     if !new_reward_farm {
@@ -251,50 +1781,201 @@ pub fn process_new_reward(
     if reward_messages.is_empty() {
         return Ok(None); // Early return with None
     }
-    let user = accounts.get(0);
-    let farm_id = accounts.get(1);
-    // could get rewards tokens from messages, but are only given the token account not the mint address
-    //lp mint token account in accounts, but we need to mint address :-'(
-    //no lp mint in the logs
-
-    // println(format!("user: {:?}, farm_id: {:?}", user, farm_id));
-    // println(format!("accounts: {:?}", accounts));
+    // the logs only give the reward vault token account, its mint is resolved from the token balances
 
     //"Program log: process_admin_add_reward_token: 6npFrUXvt7yniYerAwcBjg5SKspxN4tZbGFxEqMFEZHJ, 1740785220, 1741390020, 1, 0
 
-    // // Finding the earliest start time
-    let mut start_time: u32 = 0;
-    for message in reward_messages {
-        if let Some(split) = message.split("process_admin_add_reward_token: ").nth(1) {
-            if let Some(temp_start_time) =
-                split.split(", ").nth(1).and_then(|s| s.parse::<u32>().ok())
-            {
-                start_time = if start_time == 0 {
-                    temp_start_time
-                } else {
-                    start_time.min(temp_start_time)
-                };
-            }
-        }
-    }
-
-    // // Finding the latest end time
-    let mut end_time: u32 = 0;
-    for message in reward_messages {
-        if let Some(split) = message.split("process_admin_add_reward_token: ").nth(1) {
-            if let Some(temp_end_time) =
-                split.split(", ").nth(2).and_then(|s| s.parse::<u32>().ok())
-            {
-                end_time = end_time.max(temp_end_time);
-            }
-        }
-    }
+    let rewards = reward_messages
+        .iter()
+        .filter_map(|message| {
+            parse_reward_update_schedule("process_admin_add_reward_token: ", message)
+        })
+        .collect::<Vec<RewardSchedule>>();
+    let (start_time, end_time) = schedule_window(&rewards);
 
     Ok(Some(NewRewardTransaction {
         signature: signature.to_string(),
-        farm_id: farm_id.unwrap().to_string(),
-        user: user.unwrap().to_string(),
+        farm_id: farm_id.to_string(),
+        user: user.to_string(),
         start_time,
         end_time,
+        rewards,
+        lp_mint: String::new(),
+        creation_signature: String::new(),
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logs(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    fn reward_schedule(start_time: u32, end_time: u32) -> RewardSchedule {
+        RewardSchedule {
+            reward_per_second: 16,
+            start_time,
+            end_time,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reward_update_kind_from_schedules() {
+        let previous_reward = reward_schedule(1740172411, 1740777211);
+
+        // restarted after the previous schedule ended
+        assert_eq!(
+            reward_update_kind(Some(&previous_reward), 1740777250),
+            RewardUpdateKind::Restart
+        );
+        // restarted at the previous end time
+        assert_eq!(
+            reward_update_kind(Some(&previous_reward), 1740777211),
+            RewardUpdateKind::Restart
+        );
+        // topped up or extended while running
+        assert_eq!(
+            reward_update_kind(Some(&previous_reward), 1740500000),
+            RewardUpdateKind::Extend
+        );
+        assert_eq!(
+            reward_update_kind(None, 1740777250),
+            RewardUpdateKind::Unknown
+        );
+    }
+
+    #[test]
+    fn process_initialize_schedule_window() {
+        let log_messages = logs(&[
+            "Program FarmqiPv5eAj3j1GMdMCMUGXqPUvmquZtMy86QH6rzhG invoke [1]",
+            "Program log: process_initialize accounts len:17",
+            "Program log: process_initialize reward_per_second 1653, begin:1737491275, current:1737490622, end:1738096075",
+            "Program log: process_initialize reward_per_second 3, begin:1737491287, current:1737490622, end:1738096087",
+        ]);
+        let initialize_txn = process_initialize(
+            &log_messages,
+            &"signature".to_string(),
+            &"farm".to_string(),
+            &"user".to_string(),
+            &"lp_mint".to_string(),
+            LpMintSource::Instruction,
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(initialize_txn.rewards.len(), 2);
+        assert_eq!(initialize_txn.rewards[1].reward_per_second, 3);
+        assert_eq!(initialize_txn.rewards[1].current_time, 1737490622);
+        assert_eq!(
+            (initialize_txn.start_time, initialize_txn.end_time),
+            (1737491275, 1738096087)
+        );
+    }
+
+    #[test]
+    fn process_restart_or_add_schedule_window() {
+        let log_messages = logs(&[
+            "Program FarmqiPv5eAj3j1GMdMCMUGXqPUvmquZtMy86QH6rzhG invoke [1]",
+            "Program log: process_creator_restart: EVfHjrgu9KFV4889AdyBNtB7jgBhAaPZeSAJ9sY163vD, 1740777211, 1741382011, 16",
+            "Program log: process_creator_restart: DpiGX6UpwH7pz9YKka2t6zyWFfBQyiq4ihCy7nzGciEh, 1740777232, 1741382032, 3",
+        ]);
+        let restart_or_add_txn = process_restart_or_add(
+            &log_messages,
+            &"signature".to_string(),
+            &"farm".to_string(),
+            &"user".to_string(),
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(
+            restart_or_add_txn.rewards[0].reward_vault,
+            "EVfHjrgu9KFV4889AdyBNtB7jgBhAaPZeSAJ9sY163vD"
+        );
+        assert_eq!(
+            (restart_or_add_txn.start_time, restart_or_add_txn.end_time),
+            (1740777211, 1741382032)
+        );
+    }
+
+    #[test]
+    fn farm_log_slices_per_invocation() {
+        let log_messages = logs(&[
+            "Program ComputeBudget111111111111111111111111111111 invoke [1]",
+            "Program ComputeBudget111111111111111111111111111111 success",
+            "Program FarmqiPv5eAj3j1GMdMCMUGXqPUvmquZtMy86QH6rzhG invoke [1]",
+            "Program log: process_creator_restart: EVfHjrgu9KFV4889AdyBNtB7jgBhAaPZeSAJ9sY163vD, 1740777211, 1741382011, 16",
+            "Program FarmqiPv5eAj3j1GMdMCMUGXqPUvmquZtMy86QH6rzhG consumed 21000 of 200000 compute units",
+            "Program FarmqiPv5eAj3j1GMdMCMUGXqPUvmquZtMy86QH6rzhG success",
+            "Program FarmqiPv5eAj3j1GMdMCMUGXqPUvmquZtMy86QH6rzhG invoke [1]",
+            "Program log: process_creator_restart: DpiGX6UpwH7pz9YKka2t6zyWFfBQyiq4ihCy7nzGciEh, 1740777232, 1741382032, 3",
+            "Program FarmqiPv5eAj3j1GMdMCMUGXqPUvmquZtMy86QH6rzhG success",
+        ]);
+
+        let slices = farm_log_slices(&log_messages);
+        assert_eq!(slices.len(), 2);
+        assert_eq!(slices[0], &log_messages[2..=5]);
+        assert_eq!(slices[1], &log_messages[6..=8]);
+    }
+
+    #[test]
+    fn farm_log_slices_with_cpi() {
+        let log_messages = logs(&[
+            // the farm program invoked by another program
+            "Program SMPLecH534NA9acpos4G6x7uf3LWbCAwZQE9e8ZekMu invoke [1]",
+            "Program FarmqiPv5eAj3j1GMdMCMUGXqPUvmquZtMy86QH6rzhG invoke [2]",
+            "Program log: Instruction: Deposit",
+            // and its own transfers to the token program
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [3]",
+            "Program log: Instruction: Transfer",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+            "Program FarmqiPv5eAj3j1GMdMCMUGXqPUvmquZtMy86QH6rzhG success",
+            "Program SMPLecH534NA9acpos4G6x7uf3LWbCAwZQE9e8ZekMu success",
+        ]);
+
+        let slices = farm_log_slices(&log_messages);
+        assert_eq!(slices, vec![&log_messages[1..=6]]);
+    }
+
+    #[test]
+    fn farm_log_slices_with_failed_invocation() {
+        let log_messages = logs(&[
+            "Program FarmqiPv5eAj3j1GMdMCMUGXqPUvmquZtMy86QH6rzhG invoke [1]",
+            "Program log: process_initialize accounts len:17",
+            "Program FarmqiPv5eAj3j1GMdMCMUGXqPUvmquZtMy86QH6rzhG failed: custom program error: 0x28",
+        ]);
+
+        assert_eq!(farm_log_slices(&log_messages), vec![&log_messages[..]]);
+    }
+
+    #[test]
+    fn farm_log_slices_with_truncated_logs() {
+        let log_messages = logs(&[
+            "Program FarmqiPv5eAj3j1GMdMCMUGXqPUvmquZtMy86QH6rzhG invoke [1]",
+            "Program log: process_initialize accounts len:17",
+            "Program FarmqiPv5eAj3j1GMdMCMUGXqPUvmquZtMy86QH6rzhG success",
+            "Program FarmqiPv5eAj3j1GMdMCMUGXqPUvmquZtMy86QH6rzhG invoke [1]",
+            "Program log: process_initialize reward_per_second 1653, begin:1737491275, current:1737490622, end:1738096075",
+            "Log truncated",
+        ]);
+
+        let slices = farm_log_slices(&log_messages);
+        assert_eq!(slices.len(), 2);
+        assert_eq!(slices[0], &log_messages[0..=2]);
+        assert_eq!(slices[1], &log_messages[3..]);
+    }
+
+    #[test]
+    fn farm_log_slices_without_farm_invocation() {
+        let log_messages = logs(&[
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [1]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+        ]);
+
+        assert!(farm_log_slices(&log_messages).is_empty());
+        assert!(farm_log_slices(&[]).is_empty());
+    }
+}
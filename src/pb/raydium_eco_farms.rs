@@ -5,11 +5,63 @@
 pub struct RaydiumEcoFarmTransactions {
     #[prost(message, repeated, tag="1")]
     pub transactions: ::prost::alloc::vec::Vec<RaydiumFarmTransaction>,
+    /// transactions that could not be processed and were skipped
+    #[prost(message, repeated, tag="2")]
+    pub diagnostics: ::prost::alloc::vec::Vec<TransactionDiagnostic>,
+    #[prost(uint64, tag="3")]
+    pub slot: u64,
+    #[prost(string, tag="4")]
+    pub block_hash: ::prost::alloc::string::String,
+    /// unix timestamp of the block, in seconds
+    #[prost(int64, tag="5")]
+    pub block_time: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TransactionDiagnostic {
+    #[prost(string, tag="1")]
+    pub signature: ::prost::alloc::string::String,
+    /// position of the transaction in the block
+    #[prost(uint32, tag="2")]
+    pub transaction_index: u32,
+    #[prost(string, tag="3")]
+    pub message: ::prost::alloc::string::String,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct RaydiumFarmTransaction {
-    #[prost(oneof="raydium_farm_transaction::Event", tags="1, 2, 3")]
+    /// index of the top-level instruction the farm program was invoked in
+    #[prost(uint32, tag="4")]
+    pub instruction_index: u32,
+    /// position within the inner instructions of `instruction_index`, unset for top-level invocations
+    #[prost(uint32, optional, tag="5")]
+    pub inner_instruction_index: ::core::option::Option<u32>,
+    /// 1 for top-level invocations, > 1 when invoked through CPI
+    #[prost(uint32, tag="6")]
+    pub stack_height: u32,
+    #[prost(enumeration="TransactionStatus", tag="7")]
+    pub status: i32,
+    /// decoded transaction error, empty for successful transactions
+    #[prost(string, tag="8")]
+    pub error: ::prost::alloc::string::String,
+    /// first signer of the transaction, the events' `user` is the farm instruction's signer
+    #[prost(string, tag="9")]
+    pub fee_payer: ::prost::alloc::string::String,
+    #[prost(uint64, tag="13")]
+    pub slot: u64,
+    /// unix timestamp of the block, in seconds
+    #[prost(int64, tag="14")]
+    pub block_time: i64,
+    /// position of the transaction in the block
+    #[prost(uint32, tag="15")]
+    pub transaction_index: u32,
+    /// unique event key: "<signature>:<instruction_index>" or "<signature>:<instruction_index>:<inner_instruction_index>"
+    #[prost(string, tag="16")]
+    pub id: ::prost::alloc::string::String,
+    /// position of the event in the block's events
+    #[prost(uint32, tag="17")]
+    pub ordinal: u32,
+    #[prost(oneof="raydium_farm_transaction::Event", tags="1, 2, 3, 10, 11, 12, 18")]
     pub event: ::core::option::Option<raydium_farm_transaction::Event>,
 }
 /// Nested message and enum types in `RaydiumFarmTransaction`.
@@ -23,6 +75,15 @@ pub mod raydium_farm_transaction {
         RestartOrAdd(super::RestartOrAddTransaction),
         #[prost(message, tag="3")]
         NewReward(super::NewRewardTransaction),
+        #[prost(message, tag="10")]
+        Deposit(super::DepositTransaction),
+        #[prost(message, tag="11")]
+        Withdraw(super::WithdrawTransaction),
+        /// zero amount deposit or withdraw, only paying out the pending rewards
+        #[prost(message, tag="12")]
+        Harvest(super::HarvestTransaction),
+        #[prost(message, tag="18")]
+        CreatorWithdrawReward(super::CreatorWithdrawRewardTransaction),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -40,6 +101,10 @@ pub struct InitializeTransaction {
     pub start_time: u32,
     #[prost(uint32, tag="6")]
     pub end_time: u32,
+    #[prost(message, repeated, tag="7")]
+    pub rewards: ::prost::alloc::vec::Vec<RewardSchedule>,
+    #[prost(enumeration="LpMintSource", tag="8")]
+    pub lp_mint_source: i32,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -54,6 +119,19 @@ pub struct RestartOrAddTransaction {
     pub start_time: u32,
     #[prost(uint32, tag="5")]
     pub end_time: u32,
+    #[prost(message, repeated, tag="6")]
+    pub rewards: ::prost::alloc::vec::Vec<RewardSchedule>,
+    /// LP mint and Init signature of the farm, only set by `map_enriched_farm_txns`
+    #[prost(string, tag="7")]
+    pub lp_mint: ::prost::alloc::string::String,
+    #[prost(string, tag="8")]
+    pub creation_signature: ::prost::alloc::string::String,
+    /// restart of an ended reward or extension of a running one, only set by `map_enriched_farm_txns`
+    #[prost(enumeration="RewardUpdateKind", tag="9")]
+    pub update_kind: i32,
+    /// schedule of the reward before the instruction
+    #[prost(message, optional, tag="10")]
+    pub previous_reward: ::core::option::Option<RewardSchedule>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -68,5 +146,454 @@ pub struct NewRewardTransaction {
     pub start_time: u32,
     #[prost(uint32, tag="5")]
     pub end_time: u32,
+    #[prost(message, repeated, tag="6")]
+    pub rewards: ::prost::alloc::vec::Vec<RewardSchedule>,
+    /// LP mint and Init signature of the farm, only set by `map_enriched_farm_txns`
+    #[prost(string, tag="7")]
+    pub lp_mint: ::prost::alloc::string::String,
+    #[prost(string, tag="8")]
+    pub creation_signature: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DepositTransaction {
+    #[prost(string, tag="1")]
+    pub signature: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub farm_id: ::prost::alloc::string::String,
+    #[prost(string, tag="3")]
+    pub user: ::prost::alloc::string::String,
+    /// the user's ledger (staking position) account of the farm
+    #[prost(string, tag="4")]
+    pub user_ledger: ::prost::alloc::string::String,
+    #[prost(string, tag="5")]
+    pub user_lp_token_account: ::prost::alloc::string::String,
+    /// mint of the farm's LP vault
+    #[prost(string, tag="6")]
+    pub lp_mint: ::prost::alloc::string::String,
+    #[prost(uint64, tag="7")]
+    pub lp_amount: u64,
+    /// pending rewards harvested by the deposit
+    #[prost(message, repeated, tag="8")]
+    pub rewards: ::prost::alloc::vec::Vec<RewardPayout>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WithdrawTransaction {
+    #[prost(string, tag="1")]
+    pub signature: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub farm_id: ::prost::alloc::string::String,
+    #[prost(string, tag="3")]
+    pub user: ::prost::alloc::string::String,
+    /// the user's ledger (staking position) account of the farm
+    #[prost(string, tag="4")]
+    pub user_ledger: ::prost::alloc::string::String,
+    #[prost(string, tag="5")]
+    pub user_lp_token_account: ::prost::alloc::string::String,
+    /// mint of the farm's LP vault
+    #[prost(string, tag="6")]
+    pub lp_mint: ::prost::alloc::string::String,
+    #[prost(uint64, tag="7")]
+    pub lp_amount: u64,
+    /// pending rewards paid out by the withdraw
+    #[prost(message, repeated, tag="8")]
+    pub rewards: ::prost::alloc::vec::Vec<RewardPayout>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HarvestTransaction {
+    #[prost(string, tag="1")]
+    pub signature: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub farm_id: ::prost::alloc::string::String,
+    #[prost(string, tag="3")]
+    pub user: ::prost::alloc::string::String,
+    /// the user's ledger (staking position) account of the farm
+    #[prost(string, tag="4")]
+    pub user_ledger: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag="5")]
+    pub rewards: ::prost::alloc::vec::Vec<RewardPayout>,
+}
+/// the creator taking back the tokens left in a reward vault
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreatorWithdrawRewardTransaction {
+    #[prost(string, tag="1")]
+    pub signature: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub farm_id: ::prost::alloc::string::String,
+    #[prost(string, tag="3")]
+    pub user: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="4")]
+    pub reward: ::core::option::Option<RewardPayout>,
+}
+/// reward tokens transferred from a reward vault to the user
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RewardPayout {
+    #[prost(string, tag="1")]
+    pub reward_vault: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub reward_mint: ::prost::alloc::string::String,
+    #[prost(uint32, tag="3")]
+    pub reward_decimals: u32,
+    #[prost(string, tag="4")]
+    pub user_token_account: ::prost::alloc::string::String,
+    #[prost(uint64, tag="5")]
+    pub amount: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RewardSchedule {
+    /// the farm's reward slot, from the position of the reward in Init. Unset for restart and add
+    /// reward, which only pass the reward vault
+    #[prost(uint32, optional, tag="1")]
+    pub reward_index: ::core::option::Option<u32>,
+    #[prost(uint64, tag="2")]
+    pub reward_per_second: u64,
+    #[prost(uint32, tag="3")]
+    pub start_time: u32,
+    #[prost(uint32, tag="4")]
+    pub end_time: u32,
+    /// on-chain clock when the schedule was set, only logged by Init
+    #[prost(uint32, tag="5")]
+    pub current_time: u32,
+    #[prost(string, tag="6")]
+    pub reward_vault: ::prost::alloc::string::String,
+    /// resolved from the vault's token balances
+    #[prost(string, tag="7")]
+    pub reward_mint: ::prost::alloc::string::String,
+    #[prost(uint32, tag="8")]
+    pub reward_decimals: u32,
+}
+/// state of a farm kept by `store_farms`, see `src/keys.rs` for the key layout
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Farm {
+    #[prost(string, tag="1")]
+    pub farm_id: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub lp_mint: ::prost::alloc::string::String,
+    /// owner account of the create farm instruction
+    #[prost(string, tag="3")]
+    pub creator: ::prost::alloc::string::String,
+    #[prost(uint64, tag="4")]
+    pub creation_slot: u64,
+    #[prost(string, tag="5")]
+    pub creation_signature: ::prost::alloc::string::String,
+    /// the current schedule of the reward for a reward key, empty for the farm key
+    #[prost(message, repeated, tag="6")]
+    pub rewards: ::prost::alloc::vec::Vec<RewardSchedule>,
+    /// slot of the event that last set the entry
+    #[prost(uint64, tag="7")]
+    pub updated_slot: u64,
+}
+/// farms with a reward active at the block time (start_time <= block_time < end_time)
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ActiveFarms {
+    #[prost(uint64, tag="1")]
+    pub slot: u64,
+    #[prost(int64, tag="2")]
+    pub block_time: i64,
+    #[prost(message, repeated, tag="3")]
+    pub farms: ::prost::alloc::vec::Vec<ActiveFarm>,
+    /// farm ids of the farms that became active or inactive since the previous block
+    #[prost(string, repeated, tag="4")]
+    pub became_active: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(string, repeated, tag="5")]
+    pub became_inactive: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ActiveFarm {
+    #[prost(string, tag="1")]
+    pub farm_id: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub lp_mint: ::prost::alloc::string::String,
+    /// current schedules of the farm's active rewards
+    #[prost(message, repeated, tag="3")]
+    pub rewards: ::prost::alloc::vec::Vec<RewardSchedule>,
+}
+/// farms whose start (earliest reward start) or end (latest reward end) was crossed between the
+/// previous block and this one
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FarmScheduleEvents {
+    #[prost(uint64, tag="1")]
+    pub slot: u64,
+    #[prost(int64, tag="2")]
+    pub block_time: i64,
+    #[prost(message, repeated, tag="3")]
+    pub started: ::prost::alloc::vec::Vec<FarmStarted>,
+    #[prost(message, repeated, tag="4")]
+    pub ended: ::prost::alloc::vec::Vec<FarmEnded>,
+}
+/// the farm's first reward started, no reward of the farm was active at the previous block
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FarmStarted {
+    #[prost(string, tag="1")]
+    pub farm_id: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub lp_mint: ::prost::alloc::string::String,
+    /// the farm's rewards whose schedule time was crossed
+    #[prost(message, repeated, tag="3")]
+    pub rewards: ::prost::alloc::vec::Vec<RewardSchedule>,
+}
+/// the farm's last reward ended, no reward of the farm is active or scheduled at the block time
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FarmEnded {
+    #[prost(string, tag="1")]
+    pub farm_id: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub lp_mint: ::prost::alloc::string::String,
+    /// the farm's rewards whose schedule time was crossed
+    #[prost(message, repeated, tag="3")]
+    pub rewards: ::prost::alloc::vec::Vec<RewardSchedule>,
+}
+/// state of a farm kept by `store_farm_states`
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FarmLifecycle {
+    #[prost(string, tag="1")]
+    pub farm_id: ::prost::alloc::string::String,
+    #[prost(enumeration="FarmState", tag="2")]
+    pub state: i32,
+    /// transaction that moved the farm to the state, empty when the block time did
+    #[prost(string, tag="3")]
+    pub signature: ::prost::alloc::string::String,
+    #[prost(uint64, tag="4")]
+    pub slot: u64,
+    #[prost(int64, tag="5")]
+    pub block_time: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FarmStateTransitions {
+    #[prost(uint64, tag="1")]
+    pub slot: u64,
+    #[prost(int64, tag="2")]
+    pub block_time: i64,
+    #[prost(message, repeated, tag="3")]
+    pub transitions: ::prost::alloc::vec::Vec<FarmStateTransition>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FarmStateTransition {
+    #[prost(string, tag="1")]
+    pub farm_id: ::prost::alloc::string::String,
+    #[prost(enumeration="FarmState", tag="2")]
+    pub previous_state: i32,
+    #[prost(enumeration="FarmState", tag="3")]
+    pub new_state: i32,
+    /// transaction that moved the farm to the new state, empty when the block time did
+    #[prost(string, tag="4")]
+    pub signature: ::prost::alloc::string::String,
+    /// the farm program does not allow this transition, an event was missed or misread
+    #[prost(bool, tag="5")]
+    pub invalid: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FarmTvls {
+    #[prost(uint64, tag="1")]
+    pub slot: u64,
+    #[prost(int64, tag="2")]
+    pub block_time: i64,
+    /// farms whose staked LP amount changed in the block
+    #[prost(message, repeated, tag="3")]
+    pub farms: ::prost::alloc::vec::Vec<FarmTvl>,
+}
+/// amounts are in raw LP token units
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FarmTvl {
+    #[prost(string, tag="1")]
+    pub farm_id: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub lp_mint: ::prost::alloc::string::String,
+    /// net LP amount deposited in the block, negative when more was withdrawn
+    #[prost(string, tag="3")]
+    pub tvl_delta: ::prost::alloc::string::String,
+    /// LP amount staked in the farm at the end of the block
+    #[prost(string, tag="4")]
+    pub tvl: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PositionChanges {
+    #[prost(uint64, tag="1")]
+    pub slot: u64,
+    #[prost(int64, tag="2")]
+    pub block_time: i64,
+    /// positions with a deposit, withdraw or harvest in the block
+    #[prost(message, repeated, tag="3")]
+    pub positions: ::prost::alloc::vec::Vec<PositionChange>,
+}
+/// a user's staked position in a farm, amounts are in raw LP token units
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PositionChange {
+    #[prost(string, tag="1")]
+    pub farm_id: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub user: ::prost::alloc::string::String,
+    /// net LP amount staked in the block, negative when more was withdrawn
+    #[prost(string, tag="3")]
+    pub staked_delta: ::prost::alloc::string::String,
+    /// LP amount staked at the end of the block
+    #[prost(string, tag="4")]
+    pub staked: ::prost::alloc::string::String,
+    #[prost(uint64, tag="5")]
+    pub first_deposit_slot: u64,
+    #[prost(uint64, tag="6")]
+    pub last_activity_slot: u64,
+    /// true in the block of the position's first deposit, withdraw or harvest
+    #[prost(bool, tag="7")]
+    pub opened: bool,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum TransactionStatus {
+    Success = 0,
+    Failed = 1,
+}
+impl TransactionStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            TransactionStatus::Success => "TRANSACTION_STATUS_SUCCESS",
+            TransactionStatus::Failed => "TRANSACTION_STATUS_FAILED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "TRANSACTION_STATUS_SUCCESS" => Some(Self::Success),
+            "TRANSACTION_STATUS_FAILED" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum LpMintSource {
+    Unknown = 0,
+    /// LP mint account of the Init instruction, matches the LP vault's token balance
+    Verified = 1,
+    /// LP mint account of the Init instruction, the LP vault has no token balance to check it against
+    Instruction = 2,
+    /// mint of the LP vault's token balance, the instruction account was missing or did not match
+    TokenBalance = 3,
+}
+impl LpMintSource {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            LpMintSource::Unknown => "LP_MINT_SOURCE_UNKNOWN",
+            LpMintSource::Verified => "LP_MINT_SOURCE_VERIFIED",
+            LpMintSource::Instruction => "LP_MINT_SOURCE_INSTRUCTION",
+            LpMintSource::TokenBalance => "LP_MINT_SOURCE_TOKEN_BALANCE",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "LP_MINT_SOURCE_UNKNOWN" => Some(Self::Unknown),
+            "LP_MINT_SOURCE_VERIFIED" => Some(Self::Verified),
+            "LP_MINT_SOURCE_INSTRUCTION" => Some(Self::Instruction),
+            "LP_MINT_SOURCE_TOKEN_BALANCE" => Some(Self::TokenBalance),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum FarmState {
+    /// the farm was not seen yet
+    Unknown = 0,
+    /// created, no reward started yet
+    Scheduled = 1,
+    /// a reward is active at the block time
+    Active = 2,
+    /// every reward ended and none is scheduled
+    Ended = 3,
+    /// a reward was restarted and has not started yet
+    Restarted = 4,
+    /// a reward was added and has not started yet
+    RewardAdded = 5,
+    /// the creator withdrew the rewards left in an ended farm
+    Closed = 6,
+}
+impl FarmState {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            FarmState::Unknown => "FARM_STATE_UNKNOWN",
+            FarmState::Scheduled => "FARM_STATE_SCHEDULED",
+            FarmState::Active => "FARM_STATE_ACTIVE",
+            FarmState::Ended => "FARM_STATE_ENDED",
+            FarmState::Restarted => "FARM_STATE_RESTARTED",
+            FarmState::RewardAdded => "FARM_STATE_REWARD_ADDED",
+            FarmState::Closed => "FARM_STATE_CLOSED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "FARM_STATE_UNKNOWN" => Some(Self::Unknown),
+            "FARM_STATE_SCHEDULED" => Some(Self::Scheduled),
+            "FARM_STATE_ACTIVE" => Some(Self::Active),
+            "FARM_STATE_ENDED" => Some(Self::Ended),
+            "FARM_STATE_RESTARTED" => Some(Self::Restarted),
+            "FARM_STATE_REWARD_ADDED" => Some(Self::RewardAdded),
+            "FARM_STATE_CLOSED" => Some(Self::Closed),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum RewardUpdateKind {
+    /// no previous schedule known for the reward
+    Unknown = 0,
+    /// the previous schedule had ended at the block time: the reward is restarted
+    Restart = 1,
+    /// the previous schedule was still running at the block time: the reward is topped up or extended
+    Extend = 2,
+}
+impl RewardUpdateKind {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            RewardUpdateKind::Unknown => "REWARD_UPDATE_KIND_UNKNOWN",
+            RewardUpdateKind::Restart => "REWARD_UPDATE_KIND_RESTART",
+            RewardUpdateKind::Extend => "REWARD_UPDATE_KIND_EXTEND",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "REWARD_UPDATE_KIND_UNKNOWN" => Some(Self::Unknown),
+            "REWARD_UPDATE_KIND_RESTART" => Some(Self::Restart),
+            "REWARD_UPDATE_KIND_EXTEND" => Some(Self::Extend),
+            _ => None,
+        }
+    }
 }
 // @@protoc_insertion_point(module)
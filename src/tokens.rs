@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use crate::pb::sf::solana::r#type::v1::{InnerInstruction, TransactionStatusMeta};
+
+const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+const TRANSFER: u8 = 3;
+const TRANSFER_CHECKED: u8 = 12;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TokenAccount {
+    pub mint: String,
+    pub decimals: u32,
+    // raw amounts, 0 when the account has no balance on that side (created or closed)
+    pub pre_amount: u64,
+    pub post_amount: u64,
+}
+
+impl TokenAccount {
+    pub fn received(&self) -> u64 {
+        self.post_amount.saturating_sub(self.pre_amount)
+    }
+
+    pub fn sent(&self) -> u64 {
+        self.pre_amount.saturating_sub(self.post_amount)
+    }
+}
+
+// Token accounts touched by the transaction keyed by address, with their mint, decimals and amounts
+// taken from the pre and post token balances
+pub fn token_accounts(
+    meta: &TransactionStatusMeta,
+    accounts: &[String],
+) -> HashMap<String, TokenAccount> {
+    let mut token_accounts: HashMap<String, TokenAccount> = HashMap::new();
+
+    let balances = meta
+        .pre_token_balances
+        .iter()
+        .map(|balance| (balance, true))
+        .chain(
+            meta.post_token_balances
+                .iter()
+                .map(|balance| (balance, false)),
+        );
+    for (balance, is_pre) in balances {
+        let address = match accounts.get(balance.account_index as usize) {
+            Some(address) => address,
+            None => continue,
+        };
+        let (decimals, amount) = balance
+            .ui_token_amount
+            .as_ref()
+            .map(|amount| {
+                (
+                    amount.decimals,
+                    amount.amount.parse::<u64>().unwrap_or_default(),
+                )
+            })
+            .unwrap_or_default();
+
+        let token_account = token_accounts.entry(address.to_string()).or_default();
+        token_account.mint = balance.mint.to_string();
+        token_account.decimals = decimals;
+        if is_pre {
+            token_account.pre_amount = amount;
+        } else {
+            token_account.post_amount = amount;
+        }
+    }
+
+    token_accounts
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenTransfer {
+    pub source: String,
+    pub destination: String,
+    pub amount: u64,
+}
+
+// SPL token `Transfer` / `TransferChecked` instructions among `instructions`
+pub fn token_transfers(
+    instructions: &[&InnerInstruction],
+    accounts: &[String],
+) -> Vec<TokenTransfer> {
+    let account = |instruction: &InnerInstruction, position: usize| {
+        instruction
+            .accounts
+            .get(position)
+            .and_then(|index| accounts.get(*index as usize))
+            .cloned()
+    };
+
+    instructions
+        .iter()
+        .filter(|instruction| {
+            accounts
+                .get(instruction.program_id_index as usize)
+                .map(|program_id| {
+                    program_id == TOKEN_PROGRAM_ID || program_id == TOKEN_2022_PROGRAM_ID
+                })
+                .unwrap_or(false)
+        })
+        .filter_map(|&instruction| {
+            let amount = instruction
+                .data
+                .get(1..9)
+                .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))?;
+
+            // transfer: source, destination, authority
+            // transfer checked: source, mint, destination, authority
+            let destination_position = match *instruction.data.first()? {
+                TRANSFER => 1,
+                TRANSFER_CHECKED => 2,
+                _ => return None,
+            };
+
+            Some(TokenTransfer {
+                source: account(instruction, 0)?,
+                destination: account(instruction, destination_position)?,
+                amount,
+            })
+        })
+        .collect()
+}
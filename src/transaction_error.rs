@@ -0,0 +1,221 @@
+// Decoder for `TransactionStatusMeta.err`, which holds the bincode serialized `TransactionError`.
+// Only the variant names and the payloads useful to identify a failed farm instruction are kept.
+
+const TRANSACTION_ERRORS: [&str; 38] = [
+    "AccountInUse",
+    "AccountLoadedTwice",
+    "AccountNotFound",
+    "ProgramAccountNotFound",
+    "InsufficientFundsForFee",
+    "InvalidAccountForFee",
+    "AlreadyProcessed",
+    "BlockhashNotFound",
+    "InstructionError",
+    "CallChainTooDeep",
+    "MissingSignatureForFee",
+    "InvalidAccountIndex",
+    "SignatureFailure",
+    "InvalidProgramForExecution",
+    "SanitizeFailure",
+    "ClusterMaintenance",
+    "AccountBorrowOutstanding",
+    "WouldExceedMaxBlockCostLimit",
+    "UnsupportedVersion",
+    "InvalidWritableAccount",
+    "WouldExceedMaxAccountCostLimit",
+    "WouldExceedAccountDataBlockLimit",
+    "TooManyAccountLocks",
+    "AddressLookupTableNotFound",
+    "InvalidAddressLookupTableOwner",
+    "InvalidAddressLookupTableData",
+    "InvalidAddressLookupTableIndex",
+    "InvalidRentPayingAccount",
+    "WouldExceedMaxVoteCostLimit",
+    "WouldExceedAccountDataTotalLimit",
+    "DuplicateInstruction",
+    "InsufficientFundsForRent",
+    "MaxLoadedAccountsDataSizeExceeded",
+    "InvalidLoadedAccountsDataSizeLimit",
+    "ResanitizationNeeded",
+    "ProgramExecutionTemporarilyRestricted",
+    "UnbalancedTransaction",
+    "ProgramCacheHitMaxLimit",
+];
+
+const INSTRUCTION_ERRORS: [&str; 54] = [
+    "GenericError",
+    "InvalidArgument",
+    "InvalidInstructionData",
+    "InvalidAccountData",
+    "AccountDataTooSmall",
+    "InsufficientFunds",
+    "IncorrectProgramId",
+    "MissingRequiredSignature",
+    "AccountAlreadyInitialized",
+    "UninitializedAccount",
+    "UnbalancedInstruction",
+    "ModifiedProgramId",
+    "ExternalAccountLamportSpend",
+    "ExternalAccountDataModified",
+    "ReadonlyLamportChange",
+    "ReadonlyDataModified",
+    "DuplicateAccountIndex",
+    "ExecutableModified",
+    "RentEpochModified",
+    "NotEnoughAccountKeys",
+    "AccountDataSizeChanged",
+    "AccountNotExecutable",
+    "AccountBorrowFailed",
+    "AccountBorrowOutstanding",
+    "DuplicateAccountOutOfSync",
+    "Custom",
+    "InvalidError",
+    "ExecutableDataModified",
+    "ExecutableLamportChange",
+    "ExecutableAccountNotRentExempt",
+    "UnsupportedProgramId",
+    "CallDepth",
+    "MissingAccount",
+    "ReentrancyNotAllowed",
+    "MaxSeedLengthExceeded",
+    "InvalidSeeds",
+    "InvalidRealloc",
+    "ComputationalBudgetExceeded",
+    "PrivilegeEscalation",
+    "ProgramEnvironmentSetupFailure",
+    "ProgramFailedToComplete",
+    "ProgramFailedToCompile",
+    "Immutable",
+    "IncorrectAuthority",
+    "BorshIoError",
+    "AccountNotRentExempt",
+    "InvalidAccountOwner",
+    "ArithmeticOverflow",
+    "UnsupportedSysvar",
+    "IllegalOwner",
+    "MaxAccountsDataAllocationsExceeded",
+    "MaxAccountsExceeded",
+    "MaxInstructionTraceLengthExceeded",
+    "BuiltinProgramsMustConsumeComputeUnits",
+];
+
+const INSTRUCTION_ERROR: u32 = 8;
+const DUPLICATE_INSTRUCTION: u32 = 30;
+const INSUFFICIENT_FUNDS_FOR_RENT: u32 = 31;
+const PROGRAM_EXECUTION_TEMPORARILY_RESTRICTED: u32 = 35;
+const CUSTOM_INSTRUCTION_ERROR: u32 = 25;
+
+// e.g. "InstructionError(2, Custom(40))", "InstructionError(0, InvalidAccountData)", "BlockhashNotFound"
+pub fn decode_transaction_error(err: &[u8]) -> String {
+    let variant = match read_u32(err, 0) {
+        Some(variant) => variant,
+        None => return format!("Unknown({:?})", err),
+    };
+    let name = match TRANSACTION_ERRORS.get(variant as usize) {
+        Some(name) => name,
+        None => return format!("Unknown({})", variant),
+    };
+
+    match variant {
+        INSTRUCTION_ERROR => {
+            let instruction_index = err.get(4).copied().unwrap_or_default();
+            format!(
+                "{}({}, {})",
+                name,
+                instruction_index,
+                decode_instruction_error(err.get(5..).unwrap_or_default())
+            )
+        }
+        DUPLICATE_INSTRUCTION
+        | INSUFFICIENT_FUNDS_FOR_RENT
+        | PROGRAM_EXECUTION_TEMPORARILY_RESTRICTED => {
+            let index = err.get(4).copied().unwrap_or_default();
+            format!("{}({})", name, index)
+        }
+        _ => name.to_string(),
+    }
+}
+
+fn decode_instruction_error(err: &[u8]) -> String {
+    let variant = match read_u32(err, 0) {
+        Some(variant) => variant,
+        None => return "Unknown".to_string(),
+    };
+
+    match (variant, INSTRUCTION_ERRORS.get(variant as usize)) {
+        (CUSTOM_INSTRUCTION_ERROR, Some(name)) => {
+            format!("{}({})", name, read_u32(err, 4).unwrap_or_default())
+        }
+        (_, Some(name)) => name.to_string(),
+        (_, None) => format!("Unknown({})", variant),
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_custom_instruction_error() {
+        // InstructionError(2, Custom(40))
+        let err = [8, 0, 0, 0, 2, 25, 0, 0, 0, 40, 0, 0, 0];
+        assert_eq!(
+            decode_transaction_error(&err),
+            "InstructionError(2, Custom(40))"
+        );
+    }
+
+    #[test]
+    fn decode_instruction_error() {
+        let err = [8, 0, 0, 0, 0, 3, 0, 0, 0];
+        assert_eq!(
+            decode_transaction_error(&err),
+            "InstructionError(0, InvalidAccountData)"
+        );
+    }
+
+    #[test]
+    fn decode_unknown_instruction_error() {
+        let err = [8, 0, 0, 0, 1, 200, 0, 0, 0];
+        assert_eq!(
+            decode_transaction_error(&err),
+            "InstructionError(1, Unknown(200))"
+        );
+    }
+
+    #[test]
+    fn decode_truncated_instruction_error() {
+        assert_eq!(
+            decode_transaction_error(&[8, 0, 0, 0]),
+            "InstructionError(0, Unknown)"
+        );
+        assert_eq!(
+            decode_transaction_error(&[8, 0, 0, 0, 2, 25, 0]),
+            "InstructionError(2, Unknown)"
+        );
+    }
+
+    #[test]
+    fn decode_transaction_errors() {
+        assert_eq!(decode_transaction_error(&[7, 0, 0, 0]), "BlockhashNotFound");
+        assert_eq!(
+            decode_transaction_error(&[30, 0, 0, 0, 3]),
+            "DuplicateInstruction(3)"
+        );
+        assert_eq!(
+            decode_transaction_error(&[31, 0, 0, 0, 1]),
+            "InsufficientFundsForRent(1)"
+        );
+    }
+
+    #[test]
+    fn decode_unknown_transaction_error() {
+        assert_eq!(decode_transaction_error(&[99, 0, 0, 0]), "Unknown(99)");
+        assert_eq!(decode_transaction_error(&[1, 0]), "Unknown([1, 0])");
+    }
+}